@@ -0,0 +1,49 @@
+use std::{env, fs, path::{Path, PathBuf}};
+
+/// Generates a `golden_answers!` entry for every challenge recorded in a `src/year<year>/day<day>/answers` file,
+/// so a golden test exists as soon as an answer is recorded
+fn main() {
+    println!("cargo:rerun-if-changed=src");
+
+    let mut entries = vec![];
+    for (year, year_path) in numbered_dirs(Path::new("src"), "year") {
+        for (day, day_path) in numbered_dirs(&year_path, "day") {
+            let text = fs::read_to_string(day_path.join("answers")).unwrap_or_default();
+            for part in recorded_parts(&text) {
+                entries.push(format!("    year{0}_day{1}_part{2} => (\"{0}\", \"{1}\", \"{2}\"),\n", year, day, part));
+            }
+        }
+    }
+
+    let out_path = Path::new(&env::var("OUT_DIR").unwrap()).join("golden_answers.rs");
+    fs::write(out_path, format!("golden_answers! {{\n{}}}\n", entries.concat())).unwrap();
+}
+
+/// `<prefix><number>` directories in `path`, in numeric order
+fn numbered_dirs(path: &Path, prefix: &str) -> Vec<(u32, PathBuf)> {
+    let mut dirs: Vec<(u32, PathBuf)> = fs::read_dir(path).into_iter().flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            Some((name.strip_prefix(prefix)?.parse().ok()?, entry.path()))
+        })
+        .collect();
+    dirs.sort_unstable();
+
+    dirs
+}
+
+/// Parts with an answer in the answers file format, rejections and continuation lines are skipped
+fn recorded_parts(text: &str) -> Vec<u8> {
+    let mut parts: Vec<u8> = text.lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('|') && !line.starts_with('#'))
+        .filter_map(|line| line.split_once(':'))
+        .filter_map(|(key, _)| key.trim().strip_suffix("correct").unwrap_or(key).trim().parse().ok())
+        .collect();
+    parts.sort_unstable();
+    parts.dedup();
+
+    parts
+}
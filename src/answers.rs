@@ -0,0 +1,307 @@
+use std::{collections::{BTreeMap, BTreeSet}, fmt::Display, fs, io, path::{Path, PathBuf}};
+
+use crate::handler::Part;
+use crate::load_input;
+
+/// Known-good answers for a day, stored next to `input` as `<part>: <answer>` lines when recorded locally,
+/// `<part> correct: <answer>` once the server accepted them and `<part> rejected: <answer> (<reason>)` when it turned them down.
+/// A multi-line answer is written as `<part>:`, or `<part> rejected: (<reason>)`, followed by one `| <line>` per line
+#[derive(Debug, Default)]
+pub struct Answers {
+    answers: BTreeMap<Part, String>,
+    /// Parts whose answer the server accepted
+    confirmed: BTreeSet<Part>,
+    rejected: BTreeMap<Part, Vec<Rejection>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rejection {
+    pub answer: String,
+    /// Outcome given by the server, `too high` and `too low` also rule out answers past them
+    pub reason: String,
+}
+
+#[derive(Debug)]
+pub enum AnswersError {
+    ReadError(String),
+    WriteError(String),
+    Parse(String),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CheckStatus {
+    Pass,
+    Fail(String),
+    Unknown,
+}
+
+impl Display for CheckStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckStatus::Pass => write!(f, "pass"),
+            CheckStatus::Fail(expected) => write!(f, "FAIL (expected {})", expected),
+            CheckStatus::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+pub fn path(day_num: &str, year: &str, base_path_opt: Option<&str>) -> PathBuf {
+    load_input::day_path(day_num, year, base_path_opt).join("answers")
+}
+
+/// Expected answers for an example input file, `example` has them in `example.answers`
+pub fn example_path(example_path: &Path) -> PathBuf {
+    example_path.with_extension("answers")
+}
+
+impl Answers {
+    /// Reads the answers file, a missing file means no answers are known yet
+    pub fn load(path: &Path) -> Result<Answers, AnswersError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Answers::parse(&text),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => Err(AnswersError::ReadError(format!("{} - {}", e, path.display()))),
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Answers, AnswersError> {
+        let mut parsed = Answers::default();
+        // part of the last answer line and whether it was a rejection, continuation lines are appended to it
+        let mut multi_line: Option<(Part, bool, Vec<&str>)> = None;
+        for (idx, raw_line) in text.lines().enumerate() {
+            if let Some(continued) = raw_line.trim_start().strip_prefix('|') {
+                let (part, rejected, lines) = multi_line.as_mut()
+                    .ok_or_else(|| AnswersError::Parse(format!("line {}: '|' continues no answer", idx + 1)))?;
+                lines.push(continued.strip_prefix(' ').unwrap_or(continued));
+                if *rejected {
+                    if let Some(rejection) = parsed.rejected.get_mut(part).and_then(|rejections| rejections.last_mut()) {
+                        rejection.answer = lines.join("\n");
+                    }
+                } else {
+                    parsed.answers.insert(*part, lines.join("\n"));
+                }
+                continue;
+            }
+            multi_line = None;
+            let line = raw_line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, answer) = line.split_once(':')
+                .ok_or_else(|| AnswersError::Parse(format!("line {}: expected '<part>: <answer>'", idx + 1)))?;
+            let key = key.trim();
+            let (part, rejected, confirmed) = match (key.strip_suffix("rejected"), key.strip_suffix("correct")) {
+                (Some(part), _) => (part, true, false),
+                (_, Some(part)) => (part, false, true),
+                _ => (key, false, false),
+            };
+            let part = part.parse::<Part>()
+                .map_err(|e| AnswersError::Parse(format!("line {}: {}", idx + 1, e)))?;
+            let answer = answer.trim();
+            if rejected && answer.starts_with('(') && !answer.contains(" (") {
+                let reason = answer.trim_start_matches('(').trim_end_matches(')');
+                parsed.rejected.entry(part).or_default().push(Rejection { answer: String::new(), reason: reason.to_string() });
+                multi_line = Some((part, true, vec![]));
+            } else if rejected {
+                let (answer, reason) = answer.strip_suffix(')')
+                    .and_then(|answer| answer.rsplit_once(" ("))
+                    .unwrap_or((answer, "wrong"));
+                parsed.reject(part, answer, reason);
+            } else {
+                parsed.answers.insert(part, answer.to_string());
+                if confirmed {
+                    parsed.confirmed.insert(part);
+                }
+                if answer.is_empty() {
+                    multi_line = Some((part, false, vec![]));
+                }
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), AnswersError> {
+        fs::write(path, self.to_text()).map_err(|e| AnswersError::WriteError(format!("{} - {}", e, path.display())))
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for (part, answer) in &self.answers {
+            let key = if self.confirmed.contains(part) { format!("{} correct", part) } else { part.to_string() };
+            if answer.contains('\n') {
+                text.push_str(&format!("{}:\n", key));
+                push_lines(&mut text, answer);
+            } else {
+                text.push_str(&format!("{}: {}\n", key, answer));
+            }
+        }
+        for (part, rejections) in &self.rejected {
+            for rejection in rejections {
+                if rejection.answer.contains('\n') {
+                    text.push_str(&format!("{} rejected: ({})\n", part, rejection.reason));
+                    push_lines(&mut text, &rejection.answer);
+                } else {
+                    text.push_str(&format!("{} rejected: {} ({})\n", part, rejection.answer, rejection.reason));
+                }
+            }
+        }
+
+        text
+    }
+
+    pub fn get(&self, part: Part) -> Option<&str> {
+        self.answers.get(&part).map(|answer| answer.as_str())
+    }
+
+    /// Records an answer for a part that has none yet, returns whether anything changed
+    pub fn record(&mut self, part: Part, answer: &str) -> bool {
+        if self.answers.contains_key(&part) {
+            return false;
+        }
+        self.answers.insert(part, answer.to_string());
+        true
+    }
+
+    /// Whether the server accepted the stored answer, recorded answers are unverified
+    pub fn is_confirmed(&self, part: Part) -> bool {
+        self.confirmed.contains(&part)
+    }
+
+    /// Stores an answer the server accepted, replacing any recorded one
+    pub fn confirm(&mut self, part: Part, answer: &str) {
+        self.answers.insert(part, answer.to_string());
+        self.confirmed.insert(part);
+    }
+
+    /// Stores an answer the server turned down, dropping it as the recorded answer
+    pub fn reject(&mut self, part: Part, answer: &str, reason: &str) {
+        if !self.is_confirmed(part) && self.get(part) == Some(answer) {
+            self.answers.remove(&part);
+        }
+        let rejections = self.rejected.entry(part).or_default();
+        if !rejections.iter().any(|rejection| rejection.answer == answer) {
+            rejections.push(Rejection { answer: answer.to_string(), reason: reason.to_string() });
+        }
+    }
+
+    /// Why `answer` is already known to be wrong, from the confirmed answer or earlier rejections
+    pub fn known_wrong(&self, part: Part, answer: &str) -> Option<String> {
+        if let Some(expected) = self.get(part).filter(|expected| self.is_confirmed(part) && *expected != answer) {
+            return Some(format!("the confirmed answer is {}", expected));
+        }
+        let answer_num = answer.parse::<i128>().ok();
+        self.rejected.get(&part).into_iter().flatten().find_map(|rejection| {
+            let bound = rejection.answer.parse::<i128>().ok().zip(answer_num);
+            match (rejection.reason.as_str(), bound) {
+                _ if rejection.answer == answer => Some(format!("{} was already rejected ({})", answer, rejection.reason)),
+                ("too high", Some((high, num))) if num >= high => Some(format!("{} was too high", rejection.answer)),
+                ("too low", Some((low, num))) if num <= low => Some(format!("{} was too low", rejection.answer)),
+                _ => None,
+            }
+        })
+    }
+
+    /// Compares a solve result against the recorded answer, `None` is a failed solve
+    pub fn check(&self, part: Part, answer: Option<&str>) -> CheckStatus {
+        match (self.get(part), answer) {
+            (None, _) => CheckStatus::Unknown,
+            (Some(expected), Some(answer)) if expected == answer => CheckStatus::Pass,
+            (Some(expected), _) => CheckStatus::Fail(expected.to_string()),
+        }
+    }
+}
+
+/// Continuation lines of a multi-line answer
+fn push_lines(text: &mut String, answer: &str) {
+    for line in answer.split('\n') {
+        text.push_str(&format!("|{}{}\n", if line.is_empty() { "" } else { " " }, line));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::handler::Part;
+    use super::{Answers, CheckStatus};
+
+    #[test]
+    fn parse_and_check() {
+        let answers = Answers::parse("# 2021 day 1\n1: 1564\n2: 1611\n").unwrap();
+        assert_eq!(answers.check(Part::One, Some("1564")), CheckStatus::Pass);
+        assert_eq!(answers.check(Part::Two, Some("1")), CheckStatus::Fail(String::from("1611")));
+        assert_eq!(answers.check(Part::Two, None), CheckStatus::Fail(String::from("1611")));
+        assert_eq!(answers.check(Part::Bonus(3), Some("1")), CheckStatus::Unknown);
+    }
+
+    #[test]
+    fn record_keeps_existing() {
+        let mut answers = Answers::parse("1: 10").unwrap();
+        assert!(!answers.record(Part::One, "11"));
+        assert!(answers.record(Part::Two, "12"));
+        assert_eq!(answers.get(Part::One), Some("10"));
+        assert_eq!(answers.get(Part::Two), Some("12"));
+    }
+
+    #[test]
+    fn rejections() {
+        let text = "1 correct: 10\n2 rejected: 50 (too high)\n2 rejected: 20 (too low)\n2 rejected: 33 (wrong)\n";
+        let answers = Answers::parse(text).unwrap();
+        assert!(answers.known_wrong(Part::One, "10").is_none());
+        assert!(answers.known_wrong(Part::One, "11").is_some());
+        assert!(answers.known_wrong(Part::Two, "51").is_some());
+        assert!(answers.known_wrong(Part::Two, "19").is_some());
+        assert!(answers.known_wrong(Part::Two, "33").is_some());
+        assert!(answers.known_wrong(Part::Two, "34").is_none());
+
+        let dir = std::env::temp_dir().join(format!("advent-answers-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        answers.save(&dir.join("answers")).unwrap();
+        assert_eq!(std::fs::read_to_string(dir.join("answers")).unwrap(), text);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn recorded_answers_are_unconfirmed() {
+        let mut answers = Answers::parse("1: 10\n2 correct: 20\n").unwrap();
+        assert!(!answers.is_confirmed(Part::One));
+        assert!(answers.is_confirmed(Part::Two));
+        assert!(answers.known_wrong(Part::One, "11").is_none());
+        assert!(answers.known_wrong(Part::Two, "21").is_some());
+
+        answers.reject(Part::One, "10", "too low");
+        assert_eq!(answers.get(Part::One), None);
+        assert!(answers.known_wrong(Part::One, "9").is_some());
+        answers.confirm(Part::One, "12");
+        assert_eq!(answers.to_text(), "1 correct: 12\n2 correct: 20\n1 rejected: 10 (too low)\n");
+    }
+
+    #[test]
+    fn parse_error_line() {
+        assert!(Answers::parse("1: 10\nnonsense").is_err());
+        assert!(Answers::parse("| stray").is_err());
+    }
+
+    #[test]
+    fn multi_line_round_trip() {
+        let mut answers = Answers::default();
+        let letters = "\n.##..#  \n#..#.#\n";
+        answers.record(Part::One, "17");
+        answers.record(Part::Two, letters);
+        let text = answers.to_text();
+        assert_eq!(text, "1: 17\n2:\n|\n| .##..#  \n| #..#.#\n|\n");
+
+        let parsed = Answers::parse(&text).unwrap();
+        assert_eq!(parsed.check(Part::One, Some("17")), CheckStatus::Pass);
+        assert_eq!(parsed.check(Part::Two, Some(letters)), CheckStatus::Pass);
+        assert_eq!(parsed.to_text(), text);
+
+        let mut rejected = Answers::default();
+        rejected.reject(Part::Two, letters, "wrong");
+        rejected.reject(Part::Two, "12", "too low");
+        let text = rejected.to_text();
+        assert_eq!(text, "2 rejected: (wrong)\n|\n| .##..#  \n| #..#.#\n|\n2 rejected: 12 (too low)\n");
+        let parsed = Answers::parse(&text).unwrap();
+        assert!(parsed.known_wrong(Part::Two, letters).is_some());
+        assert_eq!(parsed.to_text(), text);
+    }
+}
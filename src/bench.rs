@@ -0,0 +1,267 @@
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::handler::{ErrorKind, Part, SolutionHandler, SolveError};
+use crate::report::{csv_field, display_ms, duration_ms, OutputFormat};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BenchStats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+    pub std_dev: Duration,
+}
+
+impl BenchStats {
+    pub fn from_samples(samples: &[Duration]) -> Option<BenchStats> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+
+        let runs = sorted.len();
+        let median = if runs.is_multiple_of(2) {
+            (sorted[runs / 2 - 1] + sorted[runs / 2]) / 2
+        } else {
+            sorted[runs / 2]
+        };
+        // nearest-rank percentile
+        let p95_rank = ((runs as f64) * 0.95).ceil() as usize;
+        let mean_secs = sorted.iter().map(|sample| sample.as_secs_f64()).sum::<f64>() / runs as f64;
+        let variance = sorted.iter()
+            .map(|sample| (sample.as_secs_f64() - mean_secs).powi(2))
+            .sum::<f64>() / runs as f64;
+
+        Some(BenchStats {
+            runs,
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean_secs),
+            p95: sorted[p95_rank.max(1) - 1],
+            std_dev: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+pub struct BenchReport {
+    pub year: String,
+    pub day: String,
+    /// Name of an input given on the command line, `None` for the day's own input
+    pub input: Option<String>,
+    pub part: Part,
+    pub parse: Option<BenchStats>,
+    pub solve: Option<BenchStats>,
+    pub result: Result<String, SolveError>,
+}
+
+/// Solves `warmup` times without recording, then `runs` times, stopping at the first error
+pub fn run<'a>(solution_handler: &SolutionHandler<'a, &'a str>, year: &str, day: &str, part: Part, input: &'a str, warmup: usize, runs: usize) -> BenchReport {
+    let mut parse_samples = vec![];
+    let mut solve_samples = vec![];
+    let mut result = Err(SolveError::new(ErrorKind::NotFound, "no benchmark runs"));
+
+    for run in 0..warmup + runs {
+        let (timing, solution) = solution_handler.solve(year, day, part, input);
+        let failed = solution.is_err();
+        result = solution;
+        if failed {
+            break;
+        }
+        if run >= warmup {
+            if let Some(parse) = timing.parse {
+                parse_samples.push(parse);
+            }
+            solve_samples.push(timing.solve);
+        }
+    }
+
+    BenchReport {
+        year: year.to_string(),
+        day: day.to_string(),
+        input: None,
+        part,
+        parse: BenchStats::from_samples(&parse_samples),
+        solve: BenchStats::from_samples(&solve_samples),
+        result,
+    }
+}
+
+pub fn print(reports: &[BenchReport], format: OutputFormat) {
+    match format {
+        OutputFormat::Text => print_table(reports),
+        OutputFormat::Json => println!("{}", to_json(reports)),
+        OutputFormat::Csv => print!("{}", to_csv(reports)),
+        OutputFormat::Tap => print!("{}", to_tap(reports)),
+    }
+}
+
+fn print_table(reports: &[BenchReport]) {
+    // the input column is only shown for inputs given on the command line
+    let input_width = reports.iter().filter_map(|report| report.input.as_ref().map(|name| name.len().max(5))).max();
+    let input_cell = |name: Option<&str>| input_width
+        .map(|width| format!("{:<width$} | ", name.unwrap_or_default(), width = width))
+        .unwrap_or_default();
+
+    println!("{:<4} | {:<3} | {}{:<4} | {:<5} | {:>5} | {:>10} | {:>10} | {:>10} | {:>10} | {:>10}",
+        "Year", "Day", input_cell(Some("Input")), "Part", "Stage", "Runs", "Min (ms)", "Median", "Mean", "p95", "Std dev");
+    for report in reports {
+        let input = input_cell(report.input.as_deref());
+        if let Err(e) = &report.result {
+            println!("{:<4} | {:<3} | {}{:<4} | {}", report.year, report.day, input, report.part, e);
+            continue;
+        }
+        for (stage, stats) in report.stages() {
+            println!("{:<4} | {:<3} | {}{:<4} | {:<5} | {:>5} | {:>10} | {:>10} | {:>10} | {:>10} | {:>10}",
+                report.year, report.day, input, report.part, stage, stats.runs,
+                display_ms(&stats.min), display_ms(&stats.median), display_ms(&stats.mean),
+                display_ms(&stats.p95), display_ms(&stats.std_dev));
+        }
+    }
+}
+
+impl BenchReport {
+    /// Timed stages in run order, parse is only there for days that parse separately
+    fn stages(&self) -> Vec<(&'static str, &BenchStats)> {
+        [("parse", &self.parse), ("solve", &self.solve)].iter()
+            .filter_map(|(stage, stats_opt)| stats_opt.as_ref().map(|stats| (*stage, stats)))
+            .collect()
+    }
+}
+
+fn stats_json(stats: &BenchStats) -> Value {
+    json!({
+        "runs": stats.runs,
+        "min_ms": duration_ms(&stats.min),
+        "median_ms": duration_ms(&stats.median),
+        "mean_ms": duration_ms(&stats.mean),
+        "p95_ms": duration_ms(&stats.p95),
+        "std_dev_ms": duration_ms(&stats.std_dev),
+    })
+}
+
+pub fn to_json(reports: &[BenchReport]) -> Value {
+    Value::Array(reports.iter().map(|report| json!({
+        "year": report.year,
+        "day": report.day,
+        "input": report.input,
+        "part": report.part.to_string(),
+        "answer": report.result.as_ref().ok(),
+        "parse": report.parse.as_ref().map(stats_json),
+        "solve": report.solve.as_ref().map(stats_json),
+        "error": report.result.as_ref().err().map(|e| json!({
+            "kind": e.kind.to_string(),
+            "message": e.message,
+        })),
+    })).collect())
+}
+
+/// One row per timed stage like the text table, a failed challenge gets a single row with its error
+pub fn to_csv(reports: &[BenchReport]) -> String {
+    let mut text = String::from("year,day,input,part,stage,runs,min_ms,median_ms,mean_ms,p95_ms,std_dev_ms,error_kind,error\n");
+    for report in reports {
+        let prefix = [report.year.clone(), report.day.clone(), report.input.clone().unwrap_or_default(), report.part.to_string()];
+        let rows: Vec<Vec<String>> = match &report.result {
+            Ok(_) => report.stages().iter().map(|(stage, stats)| vec![
+                stage.to_string(),
+                stats.runs.to_string(),
+                display_ms(&stats.min),
+                display_ms(&stats.median),
+                display_ms(&stats.mean),
+                display_ms(&stats.p95),
+                display_ms(&stats.std_dev),
+                String::new(),
+                String::new(),
+            ]).collect(),
+            Err(e) => vec![vec![String::new(); 7].into_iter().chain([e.kind.to_string(), e.to_string()]).collect()],
+        };
+        for row in rows {
+            let fields: Vec<String> = prefix.iter().chain(&row).map(|field| csv_field(field)).collect();
+            text.push_str(&fields.join(","));
+            text.push('\n');
+        }
+    }
+
+    text
+}
+
+/// Test Anything Protocol, a challenge that failed to solve is `not ok`
+pub fn to_tap(reports: &[BenchReport]) -> String {
+    let mut text = format!("TAP version 13\n1..{}\n", reports.len());
+    for (idx, report) in reports.iter().enumerate() {
+        let status = if report.result.is_ok() { "ok" } else { "not ok" };
+        let directive = match &report.result {
+            Err(e) if e.kind == ErrorKind::Unimplemented => " # TODO not implemented",
+            _ => "",
+        };
+        let input = report.input.as_ref().map(|name| format!(" input {}", name)).unwrap_or_default();
+        text.push_str(&format!("{} {} - {} day {}{} part {}{}\n", status, idx + 1, report.year, report.day, input, report.part, directive));
+        text.push_str("  ---\n");
+        match &report.result {
+            Ok(_) => for (stage, stats) in report.stages() {
+                text.push_str(&format!("  {}_median_ms: {}\n", stage, display_ms(&stats.median)));
+                text.push_str(&format!("  {}_p95_ms: {}\n", stage, display_ms(&stats.p95)));
+            },
+            Err(e) => {
+                text.push_str(&format!("  kind: {}\n", e.kind));
+                text.push_str(&format!("  message: {}\n", json!(e.to_string())));
+            },
+        }
+        text.push_str("  ...\n");
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::handler::{ErrorKind, Part, SolveError};
+    use super::{to_csv, to_json, to_tap, BenchReport, BenchStats};
+
+    fn reports() -> Vec<BenchReport> {
+        let stats = BenchStats::from_samples(&[Duration::from_millis(2), Duration::from_millis(4)]);
+        vec![
+            BenchReport { year: String::from("2023"), day: String::from("1"), input: None, part: Part::One, parse: stats, solve: stats, result: Ok(String::from("42")) },
+            BenchReport { year: String::from("2023"), day: String::from("1"), input: None, part: Part::Two, parse: None, solve: None,
+                result: Err(SolveError::new(ErrorKind::Unimplemented, "part 2")) },
+        ]
+    }
+
+    #[test]
+    fn stats() {
+        let samples: Vec<Duration> = [4, 1, 3, 2].iter().map(|ms| Duration::from_millis(*ms)).collect();
+        let stats = BenchStats::from_samples(&samples).unwrap();
+        assert_eq!(stats.runs, 4);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.mean, Duration::from_micros(2500));
+        assert_eq!(stats.p95, Duration::from_millis(4));
+        assert!((stats.std_dev.as_secs_f64() - 0.001118).abs() < 1e-6);
+        assert!(BenchStats::from_samples(&[]).is_none());
+    }
+
+    #[test]
+    fn structured_output() {
+        let json = to_json(&reports());
+        assert_eq!(json[0]["solve"]["median_ms"], 3.);
+        assert_eq!(json[0]["parse"]["runs"], 2);
+        assert!(json[1]["solve"].is_null());
+        assert_eq!(json[1]["error"]["kind"], "Unimplemented");
+
+        let csv = to_csv(&reports());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[1], "2023,1,,1,parse,2,2.000,3.000,3.000,4.000,1.000,,");
+        assert_eq!(lines[2], "2023,1,,1,solve,2,2.000,3.000,3.000,4.000,1.000,,");
+        assert_eq!(lines[3], "2023,1,,2,,,,,,,,Unimplemented,Unimplemented: part 2");
+
+        let tap = to_tap(&reports());
+        assert!(tap.starts_with("TAP version 13\n1..2\nok 1 - 2023 day 1 part 1\n  ---\n  parse_median_ms: 3.000\n"), "{}", tap);
+        assert!(tap.contains("not ok 2 - 2023 day 1 part 2 # TODO not implemented\n"), "{}", tap);
+    }
+}
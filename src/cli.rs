@@ -1,14 +1,194 @@
-use clap::{App, Arg};
+use std::{ops::RangeInclusive, time::Duration};
+
+use clap::{App, Arg, ArgMatches};
+
+use crate::handler::Part;
+use crate::limits::Limits;
+use crate::report::OutputFormat;
+
+pub enum PartSelector {
+    All,
+    Only(Part),
+}
 
 pub fn init<'a>() -> clap::ArgMatches<'a> {
     App::new("Advent of Code 2021")
         .arg(Arg::with_name("generate").short("g").long("generate").help("generate a new day template (used with -d)"))
-        .arg(Arg::with_name("day").short("d").long("day").takes_value(true).required(true).help("integer indicating which day to run"))
-        .arg(Arg::with_name("challenge").short("c").long("challenge").takes_value(true).required_unless("generate").help("integer indicating which challenge to run"))
-        .arg(Arg::with_name("input").short("i").long("input").takes_value(true).help("path to input to read"))
-        .arg(Arg::with_name("session").short("s").long("session").takes_value(true).help("session information if getting data remotely"))
-        .arg(Arg::with_name("remote").short("r").long("remote").help("flag to allow remote input fetch").requires("session"))
-        .arg(Arg::with_name("year").short("y").long("year").help("run challenge from a specific year"))
+        .arg(Arg::with_name("dry-run").long("dry-run").requires("generate").help("print the files generation would write without writing them"))
+        .arg(Arg::with_name("force").long("force").requires("generate").help("regenerate the handler of a day that already exists"))
+        .arg(Arg::with_name("template").long("template").takes_value(true).requires("generate").help("handler template: lines (default), groups, grid, ints, pest or a day_<name>.rst in a template directory"))
+        .arg(Arg::with_name("template-dir").long("template-dir").takes_value(true).multiple(true).number_of_values(1).requires("generate").help("directory of templates used before <config dir>/advent/templates and the built-in ones"))
+        .arg(Arg::with_name("example-input").long("example-input").takes_value(true).requires("generate").help("example input file for the generated tests, saved as the day's example"))
+        .arg(Arg::with_name("expect").long("expect").takes_value(true).multiple(true).number_of_values(1).max_values(2).requires("generate").help("expected example answer, given once per challenge in order"))
+        .arg(Arg::with_name("fetch-puzzle").long("fetch-puzzle").help("download the puzzle description to the day's README.md, with -g its first example seeds the generated tests"))
+        .arg(Arg::with_name("day").short("d").long("day").takes_value(true).required(true).validator(is_days).help("day to run: an integer, a range like 1..=6, or all"))
+        .arg(Arg::with_name("challenge").short("c").long("challenge").takes_value(true).required_unless_one(&["generate", "fetch-puzzle"]).validator(is_part_selector).help("challenge to run: an integer or all"))
+        .arg(Arg::with_name("check").long("check").help("compare results against the recorded answers, exits non-zero on a mismatch"))
+        .arg(Arg::with_name("record").long("record").help("record results as answers for challenges without a recorded answer"))
+        .arg(Arg::with_name("submit").long("submit").conflicts_with_all(&["input", "bench"]).help("submit answers to the server, rejected answers are recorded and never submitted again"))
+        .arg(Arg::with_name("bench").long("bench").takes_value(true).validator(is_positive).help("benchmark each challenge over N runs"))
+        .arg(Arg::with_name("warmup").long("warmup").takes_value(true).default_value("3").validator(is_count).help("unrecorded runs before benchmarking"))
+        .arg(Arg::with_name("jobs").short("j").long("jobs").takes_value(true).validator(is_positive).conflicts_with("bench").help("number of challenges to solve in parallel (default 1)"))
+        .arg(Arg::with_name("timeout").long("timeout").takes_value(true).validator(is_seconds).conflicts_with("bench").help("seconds each challenge's solve may run before it is killed"))
+        .arg(Arg::with_name("max-memory").long("max-memory").takes_value(true).validator(is_mebibytes).conflicts_with("bench").help("soft memory ceiling per challenge's solve in MiB"))
+        .arg(Arg::with_name("history").long("history").takes_value(true).default_value("bench_history.csv").help("file benchmark timings are appended to"))
+        .arg(Arg::with_name("compare").long("compare").requires("bench").help("compare benchmarks against the last recorded timings, exits non-zero on a regression"))
+        .arg(Arg::with_name("threshold").long("threshold").takes_value(true).default_value("10").validator(is_percent).help("percentage slower than the baseline counted as a regression"))
+        .arg(Arg::with_name("format").long("format").takes_value(true).possible_values(&["text", "json", "csv", "tap"]).default_value("text").help("result output format"))
+        .arg(Arg::with_name("input").short("i").long("input").takes_value(true).conflicts_with_all(&["check", "record"]).help("input to solve instead of the day's input: a file, - for stdin, or a directory of input files"))
+        .arg(Arg::with_name("example").long("example").takes_value(true).min_values(0).max_values(1).validator(is_positive).conflicts_with_all(&["input", "record", "submit"])
+            .help("solve the day's Nth sample input (default 1) and check against its expected answers when known"))
+        .arg(Arg::with_name("remote").short("r").long("remote").help("flag to allow remote input fetch, the session comes from AOC_SESSION, <config dir>/advent/session or .session"))
+        .arg(Arg::with_name("base-url").long("base-url").takes_value(true).help("Advent of Code server to fetch from, defaults to https://adventofcode.com"))
+        .arg(Arg::with_name("no-cache").long("no-cache").help("always request from the server instead of the response cache in .cache/http"))
+        .arg(Arg::with_name("year").short("y").long("year").takes_value(true).help("run challenge from a specific year"))
         .version("1.0.0")
         .get_matches()
-}
\ No newline at end of file
+}
+
+pub fn parts(matches: &ArgMatches) -> Option<PartSelector> {
+    matches.value_of("challenge").map(|challenge| parse_part_selector(challenge).unwrap())
+}
+
+pub fn days(matches: &ArgMatches) -> RangeInclusive<usize> {
+    parse_days(matches.value_of("day").unwrap()).unwrap()
+}
+
+/// The day `-d` names when it is a single day, `None` for ranges and `all`
+pub fn single_day(matches: &ArgMatches) -> Option<usize> {
+    matches.value_of("day").unwrap().trim().parse().ok().filter(|day| (1..=25).contains(day))
+}
+
+pub fn format(matches: &ArgMatches) -> OutputFormat {
+    matches.value_of("format").unwrap().parse().unwrap()
+}
+
+fn parse_part_selector(value: &str) -> Result<PartSelector, String> {
+    if value == "all" {
+        return Ok(PartSelector::All);
+    }
+
+    value.parse::<Part>().map(PartSelector::Only)
+}
+
+fn parse_days(value: &str) -> Result<RangeInclusive<usize>, String> {
+    let parse_day = |day: &str| day.trim().parse::<usize>()
+        .map_err(|_| format!("invalid day '{}', expected an integer, a range like 1..=6, or all", value));
+
+    if value == "all" {
+        Ok(1..=25)
+    } else if let Some((start, end)) = value.split_once("..=") {
+        Ok(parse_day(start)?..=parse_day(end)?)
+    } else if let Some((start, end)) = value.split_once("..") {
+        let end = parse_day(end)?;
+        if end == 0 {
+            return Err(format!("invalid day range '{}'", value));
+        }
+        Ok(parse_day(start)?..=end - 1)
+    } else {
+        let day = parse_day(value)?;
+        Ok(day..=day)
+    }
+}
+
+/// Sample number given with `--example`, 1 without a number
+pub fn example(matches: &ArgMatches) -> Option<usize> {
+    if !matches.is_present("example") {
+        return None;
+    }
+
+    Some(matches.value_of("example").map_or(1, |example| example.parse().unwrap()))
+}
+
+/// Benchmark run and warmup counts, `None` when not benchmarking
+pub fn bench(matches: &ArgMatches) -> Option<(usize, usize)> {
+    let runs = matches.value_of("bench")?.parse().unwrap();
+    let warmup = matches.value_of("warmup").unwrap().parse().unwrap();
+
+    Some((runs, warmup))
+}
+
+pub fn jobs(matches: &ArgMatches) -> usize {
+    matches.value_of("jobs").map(|jobs| jobs.parse().unwrap()).unwrap_or(1)
+}
+
+pub fn limits(matches: &ArgMatches) -> Limits {
+    Limits {
+        timeout: matches.value_of("timeout").map(|secs| Duration::from_secs_f64(secs.parse().unwrap())),
+        memory: matches.value_of("max-memory").map(|mib| mib.parse::<usize>().unwrap() * (1 << 20)),
+    }
+}
+
+/// Regression threshold percentage, `None` when not comparing
+pub fn compare(matches: &ArgMatches) -> Option<f64> {
+    if !matches.is_present("compare") {
+        return None;
+    }
+
+    matches.value_of("threshold").unwrap().parse().ok()
+}
+
+fn is_count(value: String) -> Result<(), String> {
+    value.parse::<usize>().map(|_| ()).map_err(|_| format!("invalid count '{}', expected an integer", value))
+}
+
+fn is_positive(value: String) -> Result<(), String> {
+    match value.parse::<usize>() {
+        Ok(count) if count > 0 => Ok(()),
+        _ => Err(format!("invalid count '{}', expected a positive integer", value)),
+    }
+}
+
+fn is_seconds(value: String) -> Result<(), String> {
+    match value.parse::<f64>().ok().and_then(|secs| Duration::try_from_secs_f64(secs).ok()) {
+        Some(timeout) if !timeout.is_zero() => Ok(()),
+        _ => Err(format!("invalid timeout '{}', expected a positive number of seconds", value)),
+    }
+}
+
+fn is_mebibytes(value: String) -> Result<(), String> {
+    match value.parse::<usize>().ok().and_then(|mib| mib.checked_mul(1 << 20)) {
+        Some(bytes) if bytes > 0 => Ok(()),
+        _ => Err(format!("invalid memory ceiling '{}', expected a positive number of MiB", value)),
+    }
+}
+
+fn is_percent(value: String) -> Result<(), String> {
+    match value.parse::<f64>() {
+        Ok(percent) if percent >= 0. && percent.is_finite() => Ok(()),
+        _ => Err(format!("invalid percentage '{}', expected a non-negative number", value)),
+    }
+}
+
+fn is_part_selector(value: String) -> Result<(), String> {
+    parse_part_selector(&value).map(|_| ())
+}
+
+fn is_days(value: String) -> Result<(), String> {
+    parse_days(&value).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_mebibytes, is_seconds, parse_days};
+
+    #[test]
+    fn day_ranges() {
+        assert_eq!(parse_days("3"), Ok(3..=3));
+        assert_eq!(parse_days("1..=6"), Ok(1..=6));
+        assert_eq!(parse_days("1..6"), Ok(1..=5));
+        assert_eq!(parse_days("all"), Ok(1..=25));
+        assert!(parse_days("foo").is_err());
+        assert!(parse_days("1..0").is_err());
+    }
+
+    #[test]
+    fn limit_values() {
+        assert!(is_seconds(String::from("0.5")).is_ok());
+        assert!(is_seconds(String::from("0")).is_err());
+        assert!(is_seconds(String::from("1e20")).is_err());
+        assert!(is_mebibytes(String::from("512")).is_ok());
+        assert!(is_mebibytes(String::from("0")).is_err());
+        assert!(is_mebibytes(usize::MAX.to_string()).is_err());
+    }
+}
@@ -0,0 +1,240 @@
+use std::{fs, path::PathBuf, time::{Duration, Instant}};
+
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+use reqwest::header::COOKIE;
+use tokio::{sync::Mutex, time::sleep};
+
+use crate::load_input::{write_atomic, LoadError};
+use crate::session;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const CLIENT_USER_AGENT: &str = concat!("advent-2021/", env!("CARGO_PKG_VERSION"), " (+https://github.com/JScearcy/advent-2021)");
+/// Git ignored, responses are only cached once they pass validation
+pub const DEFAULT_CACHE_DIR: &str = ".cache/http";
+const DEFAULT_MIN_DELAY: Duration = Duration::from_secs(3);
+
+/// Client for every request to the Advent of Code server, requests are spaced by `min_delay`
+pub struct AocClient {
+    client: reqwest::Client,
+    base_url: String,
+    min_delay: Duration,
+    cache_dir: Option<PathBuf>,
+    last_request: Mutex<Option<Instant>>,
+}
+
+impl AocClient {
+    pub fn new(base_url: &str) -> Result<AocClient, LoadError> {
+        let client = reqwest::Client::builder().user_agent(CLIENT_USER_AGENT).build()?;
+
+        Ok(AocClient {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            min_delay: DEFAULT_MIN_DELAY,
+            cache_dir: Some(PathBuf::from(DEFAULT_CACHE_DIR)),
+            last_request: Mutex::new(None),
+        })
+    }
+
+    #[cfg(test)]
+    pub fn with_min_delay(mut self, min_delay: Duration) -> AocClient {
+        self.min_delay = min_delay;
+        self
+    }
+
+    /// `None` turns the response cache off
+    pub fn with_cache_dir(mut self, cache_dir: Option<PathBuf>) -> AocClient {
+        self.cache_dir = cache_dir;
+        self
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// Puzzle input, refused before the day unlocks
+    pub async fn input(&self, year: &str, day: &str, validate: fn(&str) -> Result<(), String>) -> Result<String, LoadError> {
+        ensure_unlocked(year, day, Utc::now())?;
+        self.get(&format!("/{}/day/{}/input", year, day), validate).await
+    }
+
+    /// GETs `path`, served from the cache when a validated response was stored before
+    pub async fn get(&self, path: &str, validate: fn(&str) -> Result<(), String>) -> Result<String, LoadError> {
+        let cached = self.cache_path(path).and_then(|cache_path| fs::read_to_string(cache_path).ok());
+        if let Some(text) = cached {
+            return Ok(text);
+        }
+
+        self.refresh(path, validate).await
+    }
+
+    /// GETs `path` ignoring any cached response, for pages that change, the response still replaces the cached one
+    pub async fn refresh(&self, path: &str, validate: fn(&str) -> Result<(), String>) -> Result<String, LoadError> {
+        let request = self.client.get(self.url(path));
+        let text = self.send(request, validate).await?;
+        if let (Some(cache_dir), Some(cache_path)) = (&self.cache_dir, self.cache_path(path)) {
+            let cached = fs::create_dir_all(cache_dir)
+                .map_err(|e| LoadError::WriteError(e.to_string()))
+                .and_then(|_| write_atomic(&cache_path, &text));
+            if let Err(e) = cached {
+                eprintln!("WARN: Response cache failure: {:?}", e);
+            }
+        }
+
+        Ok(text)
+    }
+
+    /// POSTs a form to `path`, never cached
+    pub async fn post(&self, path: &str, form: &[(&str, &str)], validate: fn(&str) -> Result<(), String>) -> Result<String, LoadError> {
+        let request = self.client.post(self.url(path)).form(form);
+        self.send(request, validate).await
+    }
+
+    fn cache_path(&self, path: &str) -> Option<PathBuf> {
+        self.cache_dir.as_ref().map(|cache_dir| cache_dir.join(cache_key(&self.url(path))))
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder, validate: fn(&str) -> Result<(), String>) -> Result<String, LoadError> {
+        let session = session::resolve()?;
+        // held until the response arrives so requests never overlap
+        let mut last_request = self.last_request.lock().await;
+        if let Some(wait) = last_request.and_then(|last| self.min_delay.checked_sub(last.elapsed())) {
+            sleep(wait).await;
+        }
+        *last_request = Some(Instant::now());
+
+        let response = request.header(COOKIE, session_cookie(&session)).send().await?;
+        let status = response.status();
+        let text = response.text().await?;
+        let first_line = text.trim().lines().next().unwrap_or_default().trim();
+        if !status.is_success() {
+            let reason = if first_line.is_empty() { status.canonical_reason().unwrap_or_default() } else { first_line };
+            return Err(LoadError::HttpError { status: Some(status), reason: reason.to_string() });
+        }
+        validate(&text).map_err(|reason| LoadError::HttpError { status: Some(status), reason })?;
+
+        Ok(text)
+    }
+}
+
+/// Cookie header for a session token, with or without its `session=` prefix
+fn session_cookie(session: &str) -> String {
+    let token = session.trim();
+    format!("session={}", token.strip_prefix("session=").unwrap_or(token))
+}
+
+fn cache_key(url: &str) -> String {
+    url.chars().map(|chr| if chr.is_ascii_alphanumeric() || chr == '-' { chr } else { '_' }).collect()
+}
+
+/// Puzzles unlock at midnight US Eastern (UTC-5) on December `day`
+pub fn unlock_time(year: i32, day: u32) -> Option<DateTime<Utc>> {
+    if !(1..=25).contains(&day) {
+        return None;
+    }
+    let eastern = FixedOffset::west(5 * 3600);
+
+    eastern.ymd_opt(year, 12, day).single().map(|date| date.and_hms(0, 0, 0).with_timezone(&Utc))
+}
+
+pub fn ensure_unlocked(year: &str, day: &str, now: DateTime<Utc>) -> Result<(), LoadError> {
+    let unlock_opt = match (year.parse(), day.parse()) {
+        (Ok(year), Ok(day)) => unlock_time(year, day),
+        _ => None,
+    };
+    match unlock_opt {
+        Some(unlock) if unlock <= now => Ok(()),
+        Some(unlock) => Err(LoadError::NotUnlocked(format!("year {} day {} unlocks at {}", year, day, unlock))),
+        None => Err(LoadError::NotUnlocked(format!("there is no puzzle for year {} day {}", year, day))),
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::{env, fs, io::{Read, Write}, net::TcpListener, thread, time::{Duration, Instant}};
+
+    use chrono::{TimeZone, Utc};
+
+    use crate::load_input::LoadError;
+    use crate::session::SESSION_ENV;
+    use super::{ensure_unlocked, session_cookie, unlock_time, AocClient};
+
+    /// Serves one request with `status` and `body`, handing back the raw request
+    pub fn serve_once(status: &'static str, body: &'static str) -> (String, thread::JoinHandle<String>) {
+        // every test fetching remotely uses the same token
+        env::set_var(SESSION_ENV, "abc123");
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = String::new();
+            let mut buffer = [0; 4096];
+            // read until the headers and any form body are in
+            while !request_complete(&request) {
+                let read = stream.read(&mut buffer).unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.push_str(&String::from_utf8_lossy(&buffer[..read]));
+            }
+            write!(stream, "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", status, body.len(), body).unwrap();
+            request
+        });
+
+        (base_url, server)
+    }
+
+    fn request_complete(request: &str) -> bool {
+        let (headers, body) = match request.split_once("\r\n\r\n") {
+            Some(split) => split,
+            None => return false,
+        };
+        let content_length = headers.lines()
+            .find_map(|line| line.to_lowercase().strip_prefix("content-length:").map(|length| length.trim().to_string()))
+            .and_then(|length| length.parse::<usize>().ok())
+            .unwrap_or(0);
+
+        body.len() >= content_length
+    }
+
+    fn accept_all(_text: &str) -> Result<(), String> { Ok(()) }
+
+    #[test]
+    fn session_prefix() {
+        assert_eq!(session_cookie("abc123"), "session=abc123");
+        assert_eq!(session_cookie("session=abc123\n"), "session=abc123");
+    }
+
+    #[test]
+    fn unlock_times() {
+        assert_eq!(unlock_time(2023, 1), Some(Utc.ymd(2023, 12, 1).and_hms(5, 0, 0)));
+        assert_eq!(unlock_time(2023, 26), None);
+        let before = Utc.ymd(2023, 12, 5).and_hms(4, 59, 59);
+        assert!(matches!(ensure_unlocked("2023", "5", before), Err(LoadError::NotUnlocked(_))));
+        assert!(ensure_unlocked("2023", "5", before + chrono::Duration::seconds(1)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn user_agent_cache_and_delay() {
+        let cache_dir = env::temp_dir().join(format!("advent-cache-{}", std::process::id()));
+        let (base_url, server) = serve_once("200 OK", "puzzle");
+        let client = AocClient::new(&base_url).unwrap().with_cache_dir(Some(cache_dir.clone()));
+
+        assert_eq!(client.get("/2021/day/1", accept_all).await.unwrap(), "puzzle");
+        let request = server.join().unwrap().to_lowercase();
+        assert!(request.contains("user-agent: advent-2021/"), "{}", request);
+        // the server is gone, so this can only come from the cache
+        assert_eq!(client.get("/2021/day/1", accept_all).await.unwrap(), "puzzle");
+        fs::remove_dir_all(&cache_dir).unwrap();
+
+        let (base_url, first) = serve_once("200 OK", "1");
+        let client = AocClient::new(&base_url).unwrap().with_cache_dir(None).with_min_delay(Duration::from_millis(200));
+        let start = Instant::now();
+        client.get("/1", accept_all).await.unwrap();
+        first.join().unwrap();
+        let (base_url, second) = serve_once("200 OK", "2");
+        let client = AocClient { base_url, ..client };
+        client.get("/2", accept_all).await.unwrap();
+        second.join().unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+}
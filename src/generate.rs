@@ -1,11 +1,20 @@
 use std::{fs, io};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use chrono::{Datelike, Utc};
 use tera::{Context,Tera};
 
+use crate::answers::{self, Answers};
+use crate::handler::Part;
+use crate::load_input;
+use crate::session;
+
 #[derive(Debug)]
 pub enum GenerateError {
     TeraError(tera::Error),
-    IoError(io::Error)
+    IoError(io::Error),
+    Invalid(String),
+    /// A module to register the day or year in does not have the generated layout
+    UnrecognizedModule(String),
 }
 
 impl From<tera::Error> for GenerateError {
@@ -15,23 +24,425 @@ impl From<io::Error> for GenerateError {
     fn from(e: io::Error) -> Self { GenerateError::IoError(e) }
 }
 
-pub fn generate_day(day_num: &str, year: &str) -> Result<(), GenerateError> {
-    let tera = Tera::new("src/templates/**/*.rst")?;
-    let day = day_num.parse::<usize>().unwrap();
+pub const FIRST_YEAR: i32 = 2015;
+pub const DEFAULT_TEMPLATE: &str = "lines";
+const BUILTIN_TEMPLATE_DIR: &str = "src/templates";
+
+#[derive(Clone, Debug)]
+pub struct GenerateOptions {
+    /// Print the files that would be written instead of writing them
+    pub dry_run: bool,
+    /// Regenerate the handler of a day that already exists
+    pub force: bool,
+    /// Handler template kind, rendered from `day_<template>.rst`
+    pub template: String,
+    /// Searched before `<config dir>/advent/templates` and the built-in templates
+    pub template_dirs: Vec<PathBuf>,
+}
+
+/// Example input and the answers expected for it, in part order. `None` leaves a part's answer blank
+#[derive(Clone, Debug, Default)]
+pub struct Example {
+    pub input: Option<String>,
+    pub answers: Vec<Option<String>>,
+}
+
+/// The examples fill in the test module and are saved as `example`, `example2`, .. with their `.answers` next to `input`.
+/// A second example is part 2's own, its test solves it instead of the first one.
+/// Only files whose contents change are written, all of them or none
+pub fn generate_day(day_arg: &str, year: &str, examples: &[Example], options: GenerateOptions) -> Result<(), GenerateError> {
+    let day = validate(day_arg, year, Utc::now().year())?;
+    let day_num = day.to_string();
+    let tera = load_templates(&options.template_dirs)?;
     let mut tera_context = Context::new();
-    tera_context.insert("day_num", day_num);
-    tera_context.insert("days", &(1..=day).collect::<Vec<usize>>());
-    let day = tera.render("day_handler.rst", &tera_context)?;
-    let year_module = tera.render("year_mod.rst", &tera_context)?;
-    
+    tera_context.insert("year", year);
+    tera_context.insert("day_num", &day_num);
+    let example_input = |example_idx: usize| examples.get(example_idx)
+        .and_then(|example| example.input.as_deref())
+        .map(|input| escape(input.trim_end_matches('\n')));
+    tera_context.insert("example", &example_input(0));
+    tera_context.insert("example_2", &example_input(1));
+    for part_idx in 0..2 {
+        // part 2 is tested against its own example when there is one
+        let example = examples.get(part_idx.min(examples.len().saturating_sub(1)));
+        if let Some(answer) = example.and_then(|example| example.answers.get(part_idx)).and_then(Option::as_ref) {
+            tera_context.insert(format!("expected_{}", part_idx + 1), &escape(answer));
+        }
+    }
+    tera_context.insert("days", &[day]);
+    let (day_handler, grammar) = render_day(&tera, &options.template, &tera_context)?;
+
     let year_dir_path: PathBuf = ["./src", &format!("year{}", year)].iter().collect();
     let dir_path: PathBuf = [&format!("{}", year_dir_path.display()), &format!("day{}", day_num)].iter().collect();
     let year_file_path: PathBuf = [&format!("{}", year_dir_path.display()), "mod.rs"].iter().collect();
     let day_file_path: PathBuf = [&format!("{}", dir_path.display()), "mod.rs"].iter().collect();
-    fs::create_dir(dir_path)?;
-    fs::write(day_file_path, day)?;
-    fs::write(year_file_path, year_module)?;
-    
+    let grammar_path: PathBuf = [&format!("{}", dir_path.display()), "grammar.pest"].iter().collect();
+    let main_path = PathBuf::from("./src/main.rs");
+    let registry_path = PathBuf::from("./src/registry.rs");
+    // the day directory may already hold a fetched README.md
+    if day_file_path.exists() && !options.force {
+        return Err(GenerateError::IoError(io::Error::new(io::ErrorKind::AlreadyExists,
+            format!("{} already exists, use --force to regenerate the handler", day_file_path.display()))));
+    }
+
+    let year_module = match fs::read_to_string(&year_file_path) {
+        Ok(year_module) => insert_day(&year_module, day)?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => tera.render("year_mod.rst", &tera_context)?,
+        Err(e) => return Err(e.into()),
+    };
+    let main = register_year_module(&fs::read_to_string(&main_path)?, year)?;
+    let registry = register_year(&fs::read_to_string(&registry_path)?, year)?;
+
+    let mut writes = vec![(day_file_path, day_handler), (year_file_path, year_module), (main_path, main), (registry_path, registry)];
+    // an existing grammar is kept even with --force, it is worked on like the handler
+    if let Some(grammar) = grammar.filter(|_| !grammar_path.exists()) {
+        writes.insert(1, (grammar_path, grammar));
+    }
+    for (example_idx, example) in examples.iter().enumerate() {
+        let example_path = dir_path.join(load_input::example_name(example_idx + 1));
+        if let Some(input) = &example.input {
+            writes.push((example_path.clone(), format!("{}\n", input.trim_end_matches('\n'))));
+        }
+        if example.answers.iter().any(Option::is_some) {
+            let mut answers = Answers::default();
+            for (part, answer) in [Part::One, Part::Two].iter().zip(&example.answers) {
+                if let Some(answer) = answer {
+                    answers.record(*part, answer);
+                }
+            }
+            writes.push((answers::example_path(&example_path), answers.to_text()));
+        }
+    }
+    let writes: Vec<(PathBuf, String)> = writes.into_iter()
+        .filter(|(path, contents)| fs::read_to_string(path).ok().as_ref() != Some(contents))
+        .collect();
+    if options.dry_run {
+        for (path, _) in &writes {
+            println!("Would {} {}", if path.exists() { "update" } else { "create" }, path.display());
+        }
+        return Ok(());
+    }
+    write_all(&writes)?;
+
     println!("Generated day {}", day_num);
     Ok(())
 }
+
+/// Templates from `template_dirs`, then `<config dir>/advent/templates` when it exists, then the built-in ones.
+/// A template in an earlier directory replaces a later one with the same name
+fn load_templates(template_dirs: &[PathBuf]) -> Result<Tera, GenerateError> {
+    let mut dirs = template_dirs.to_vec();
+    if let Some(config_templates) = session::config_dir().map(|config_dir| config_dir.join("templates")).filter(|dir| dir.is_dir()) {
+        dirs.push(config_templates);
+    }
+    dirs.push(PathBuf::from(BUILTIN_TEMPLATE_DIR));
+
+    let mut tera = Tera::default();
+    for dir in &dirs {
+        if !dir.is_dir() {
+            return Err(GenerateError::Invalid(format!("template directory {} does not exist", dir.display())));
+        }
+        // extend keeps the templates already loaded
+        tera.extend(&Tera::new(&format!("{}/**/*.rst", dir.display()))?)?;
+    }
+
+    Ok(tera)
+}
+
+/// Template kinds `tera` can render a handler from
+fn template_kinds(tera: &Tera) -> Vec<String> {
+    let mut kinds: Vec<String> = tera.get_template_names()
+        .filter(|name| !name.contains('/') && !name.ends_with(".pest.rst"))
+        .filter_map(|name| name.strip_prefix("day_")?.strip_suffix(".rst"))
+        .map(String::from)
+        .collect();
+    kinds.sort();
+
+    kinds
+}
+
+/// Handler from `day_<kind>.rst`, and a grammar from `day_<kind>.pest.rst` when the kind has one
+fn render_day(tera: &Tera, kind: &str, tera_context: &Context) -> Result<(String, Option<String>), GenerateError> {
+    let kinds = template_kinds(tera);
+    if !kinds.iter().any(|known| known == kind) {
+        return Err(GenerateError::Invalid(format!("unknown template {}, expected one of: {}", kind, kinds.join(", "))));
+    }
+    let day_handler = tera.render(&format!("day_{}.rst", kind), tera_context)?;
+    let grammar_name = format!("day_{}.pest.rst", kind);
+    let grammar = if tera.get_template_names().any(|name| name == grammar_name) {
+        Some(tera.render(&grammar_name, tera_context)?)
+    } else {
+        None
+    };
+
+    Ok((day_handler, grammar))
+}
+
+/// Contents of a generated string literal
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Day 1 to 25 of a year from 2015 up to `current_year`
+fn validate(day_arg: &str, year: &str, current_year: i32) -> Result<usize, GenerateError> {
+    let day = day_arg.parse::<usize>().ok().filter(|day| (1..=25).contains(day))
+        .ok_or_else(|| GenerateError::Invalid(format!("day must be 1 to 25, got {}", day_arg)))?;
+    year.parse::<i32>().ok().filter(|year| (FIRST_YEAR..=current_year).contains(year))
+        .ok_or_else(|| GenerateError::Invalid(format!("year must be {} to {}, got {}", FIRST_YEAR, current_year, year)))?;
+
+    Ok(day)
+}
+
+/// Writes every file or none: on a failure the files already written are restored and created directories removed
+fn write_all(writes: &[(PathBuf, String)]) -> Result<(), GenerateError> {
+    let mut written = vec![];
+    let mut created_dirs = vec![];
+    for (path, contents) in writes {
+        if let Err(e) = write_file(path, contents, &mut written, &mut created_dirs) {
+            for (path, original) in written.iter().rev() {
+                let _ = match original {
+                    Some(original) => fs::write(path, original),
+                    None => fs::remove_file(path),
+                };
+            }
+            for dir in created_dirs.iter().rev() {
+                let _ = fs::remove_dir(dir);
+            }
+            return Err(GenerateError::IoError(io::Error::new(e.kind(), format!("{} - {}, nothing was generated", e, path.display()))));
+        }
+    }
+
+    Ok(())
+}
+
+fn write_file<'a>(path: &'a Path, contents: &str, written: &mut Vec<(&'a Path, Option<String>)>, created_dirs: &mut Vec<PathBuf>) -> io::Result<()> {
+    let original = match fs::read_to_string(path) {
+        Ok(original) => Some(original),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e),
+    };
+    let mut missing_dirs: Vec<&Path> = path.ancestors().skip(1).take_while(|dir| !dir.as_os_str().is_empty() && !dir.exists()).collect();
+    missing_dirs.reverse();
+    for dir in missing_dirs {
+        fs::create_dir(dir)?;
+        created_dirs.push(dir.to_path_buf());
+    }
+    fs::write(path, contents)?;
+    written.push((path, original));
+
+    Ok(())
+}
+
+/// Adds the day's module, prelude export and handler to a year's `mod.rs`, existing days are kept
+fn insert_day(year_module: &str, day: usize) -> Result<String, GenerateError> {
+    let unrecognized = |expected: &str| GenerateError::UnrecognizedModule(format!("year mod.rs has no {} lines", expected));
+    let year_module = insert_sorted(year_module, |line| line.strip_prefix("mod day")?.strip_suffix(';')?.parse().ok(), day, &format!("mod day{};", day))
+        .ok_or_else(|| unrecognized("`mod dayN;`"))?;
+    let year_module = insert_sorted(&year_module, |line| line.strip_prefix("pub use super::day")?.split_once("::")?.0.parse().ok(), day, &format!("pub use super::day{}::Day{}Handler;", day, day))
+        .ok_or_else(|| unrecognized("`pub use super::dayN::DayNHandler;`"))?;
+
+    insert_sorted(&year_module, |line| line.strip_prefix("prelude::Day")?.strip_suffix("Handler::new(),")?.parse().ok(), day, &format!("prelude::Day{}Handler::new(),", day))
+        .ok_or_else(|| unrecognized("`prelude::DayNHandler::new(),`"))
+}
+
+/// Declares `mod year<year>;` in `main.rs`
+fn register_year_module(main: &str, year: &str) -> Result<String, GenerateError> {
+    let year_num = parse_year(year)?;
+
+    insert_sorted(main, |line| line.strip_prefix("mod year")?.strip_suffix(';')?.parse().ok(), year_num, &format!("mod year{};", year))
+        .ok_or_else(|| GenerateError::UnrecognizedModule(String::from("main.rs has no `mod yearNNNN;` lines")))
+}
+
+/// Imports and registers the year's handlers in `registry.rs`, a year newer than the default becomes the default
+fn register_year(registry: &str, year: &str) -> Result<String, GenerateError> {
+    let unrecognized = |expected: &str| GenerateError::UnrecognizedModule(format!("registry.rs has no {} line", expected));
+    let year_num = parse_year(year)?;
+    let registry = insert_sorted(registry, |line| line.strip_prefix("solution_handler.register(\"")?.split_once('"')?.0.parse().ok(), year_num,
+        &format!("solution_handler.register(\"{}\", year{}::handlers());", year, year))
+        .ok_or_else(|| unrecognized("`solution_handler.register(..)`"))?;
+
+    let mut found_import = false;
+    let mut lines = vec![];
+    for line in registry.lines() {
+        match line.strip_prefix("use crate::{").and_then(|imports| imports.strip_suffix("};")) {
+            Some(imports) => {
+                found_import = true;
+                let mut years: Vec<&str> = imports.split(',').map(str::trim).filter(|import| !import.is_empty()).collect();
+                let module = format!("year{}", year);
+                if !years.contains(&module.as_str()) {
+                    years.push(&module);
+                }
+                years.sort_unstable();
+                lines.push(format!("use crate::{{{}}};", years.join(", ")));
+            },
+            None if default_year(line).is_some_and(|default_year| year_num > default_year) => {
+                lines.push(format!("pub const DEFAULT_YEAR: &str = \"{}\";", year));
+            },
+            None => lines.push(line.to_string()),
+        }
+    }
+    if !found_import {
+        return Err(unrecognized("`use crate::{yearNNNN, ..};`"));
+    }
+
+    Ok(lines.join("\n") + "\n")
+}
+
+fn default_year(line: &str) -> Option<usize> {
+    line.strip_prefix("pub const DEFAULT_YEAR: &str = \"")?.strip_suffix("\";")?.parse().ok()
+}
+
+fn parse_year(year: &str) -> Result<usize, GenerateError> {
+    year.parse().map_err(|_| GenerateError::UnrecognizedModule(format!("{} is not a year", year)))
+}
+
+/// Inserts `line` among the lines `number` reads a number from, in number order and with their indentation.
+/// Unchanged when `value` is already there, `None` when no line has a number
+fn insert_sorted(text: &str, number: impl Fn(&str) -> Option<usize>, value: usize, line: &str) -> Option<String> {
+    let mut lines: Vec<String> = text.lines().map(String::from).collect();
+    let numbered: Vec<(usize, usize)> = lines.iter().enumerate().filter_map(|(index, line)| number(line.trim()).map(|num| (index, num))).collect();
+    let (first, _) = *numbered.first()?;
+    if numbered.iter().any(|(_, num)| *num == value) {
+        return Some(text.to_string());
+    }
+
+    let index = numbered.iter().rfind(|(_, num)| *num < value).map(|(index, _)| index + 1).unwrap_or(first);
+    let indent: String = lines[first].chars().take_while(|chr| chr.is_whitespace()).collect();
+    lines.insert(index, format!("{}{}", indent, line));
+
+    Some(lines.join("\n") + if text.ends_with('\n') { "\n" } else { "" })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use tera::Context;
+
+    use super::{insert_day, load_templates, register_year, register_year_module, render_day, template_kinds, validate, write_all, GenerateError};
+
+    const YEAR_MODULE: &str = "use crate::handler::DayHandler;
+
+mod day1;
+mod day3;
+
+pub mod prelude {
+  pub use super::day1::Day1Handler;
+  pub use super::day3::Day3Handler;
+  // all day handlers
+}
+
+pub fn handlers<'a>() -> Vec<DayHandler<'a, &'a str>> {
+  vec![
+    prelude::Day1Handler::new(),
+    prelude::Day3Handler::new(),
+  ]
+}
+";
+
+    #[test]
+    fn insert_days() {
+        let year_module = insert_day(YEAR_MODULE, 2).unwrap();
+        assert!(year_module.contains("mod day1;\nmod day2;\nmod day3;\n"), "{}", year_module);
+        assert!(year_module.contains("  pub use super::day2::Day2Handler;\n  pub use super::day3::Day3Handler;\n"), "{}", year_module);
+        assert!(year_module.contains("    prelude::Day2Handler::new(),\n    prelude::Day3Handler::new(),\n"), "{}", year_module);
+        let year_module = insert_day(&year_module, 10).unwrap();
+        assert!(year_module.contains("mod day3;\nmod day10;\n\n"), "{}", year_module);
+        assert!(year_module.contains("    prelude::Day10Handler::new(),\n  ]"), "{}", year_module);
+        assert_eq!(insert_day(&year_module, 10).unwrap(), year_module);
+        assert!(insert_day("pub fn handlers() {}", 1).is_err());
+    }
+
+    #[test]
+    fn register_years() {
+        let main = "mod registry;\nmod year2021;\nmod year2023;\n\nuse std::panic;\n";
+        assert_eq!(register_year_module(main, "2022").unwrap(), "mod registry;\nmod year2021;\nmod year2022;\nmod year2023;\n\nuse std::panic;\n");
+        assert_eq!(register_year_module(main, "2023").unwrap(), main);
+
+        let registry = "use crate::{year2021, year2023};
+
+pub const DEFAULT_YEAR: &str = \"2023\";
+
+pub fn build<'a>() -> SolutionHandler<'a, &'a str> {
+    let mut solution_handler = SolutionHandler::new();
+    solution_handler.register(\"2021\", year2021::handlers());
+    solution_handler.register(\"2023\", year2023::handlers());
+
+    solution_handler
+}
+";
+        let registered = register_year(registry, "2024").unwrap();
+        assert!(registered.starts_with("use crate::{year2021, year2023, year2024};"), "{}", registered);
+        assert!(registered.contains("DEFAULT_YEAR: &str = \"2024\";"), "{}", registered);
+        assert!(registered.contains("year2023::handlers());\n    solution_handler.register(\"2024\", year2024::handlers());\n"), "{}", registered);
+        let registered = register_year(registry, "2022").unwrap();
+        assert!(registered.contains("DEFAULT_YEAR: &str = \"2023\";"), "{}", registered);
+        assert_eq!(register_year(&registered, "2022").unwrap(), registered);
+    }
+
+    #[test]
+    fn validate_day_and_year() {
+        assert_eq!(validate("07", "2021", 2024).unwrap(), 7);
+        for (day, year) in [("foo", "2021"), ("0", "2021"), ("26", "2021"), ("1..=3", "2021"), ("1", "2014"), ("1", "2025"), ("1", "next")] {
+            assert!(matches!(validate(day, year, 2024), Err(GenerateError::Invalid(_))), "{} {}", day, year);
+        }
+    }
+
+    #[test]
+    fn failed_writes_roll_back() {
+        let dir = env::temp_dir().join(format!("advent-generate-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("main.rs"), "original").unwrap();
+        fs::write(dir.join("blocker"), "a file, not a directory").unwrap();
+        let writes = [
+            (dir.join("main.rs"), String::from("updated")),
+            (dir.join("year2030").join("day1").join("mod.rs"), String::from("handler")),
+            (dir.join("blocker").join("mod.rs"), String::from("unwritable")),
+        ];
+
+        assert!(matches!(write_all(&writes), Err(GenerateError::IoError(_))));
+        assert_eq!(fs::read_to_string(dir.join("main.rs")).unwrap(), "original");
+        assert!(!dir.join("year2030").exists());
+
+        write_all(&writes[..2]).unwrap();
+        assert_eq!(fs::read_to_string(dir.join("year2030").join("day1").join("mod.rs")).unwrap(), "handler");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn builtin_and_user_templates() {
+        let mut tera_context = Context::new();
+        tera_context.insert("year", "2023");
+        tera_context.insert("day_num", "7");
+        tera_context.insert("example", &Some("1,2"));
+        tera_context.insert("expected_1", "288");
+        let tera = load_templates(&[]).unwrap();
+        assert_eq!(template_kinds(&tera), ["grid", "groups", "ints", "lines", "pest"]);
+        for kind in template_kinds(&tera) {
+            let (day_handler, grammar) = render_day(&tera, &kind, &tera_context).unwrap();
+            assert!(day_handler.contains("impl<'a> AdventSolution<&str> for Day7Handler"), "{}", day_handler);
+            assert!(day_handler.contains("fn get_input<'a>() -> &'a str {\n\"1,2\"\n    }"), "{}", day_handler);
+            assert!(day_handler.contains("solution(Part::One).await;\n        assert!(solution == String::from(\"288\"));"), "{}", day_handler);
+            assert!(day_handler.contains("solution(Part::Two).await;\n        assert!(solution == String::from(\"\"));"), "{}", day_handler);
+            assert_eq!(grammar.is_some(), kind == "pest");
+        }
+        tera_context.insert("example_2", &Some("3,4"));
+        let (day_handler, _) = render_day(&tera, "lines", &tera_context).unwrap();
+        assert!(day_handler.contains("fn get_input_2<'a>() -> &'a str {\n\"3,4\"\n    }"), "{}", day_handler);
+        assert!(day_handler.contains("if sol == Part::Two { get_input_2() } else { get_input() }"), "{}", day_handler);
+        let (day_handler, grammar) = render_day(&tera, "pest", &tera_context).unwrap();
+        assert!(day_handler.contains("#[grammar = \"year2023/day7/grammar.pest\"]"), "{}", day_handler);
+        assert!(grammar.unwrap().contains("input = {"));
+        assert!(matches!(render_day(&tera, "nope", &tera_context), Err(GenerateError::Invalid(_))));
+
+        let dir = env::temp_dir().join(format!("advent-templates-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day_lines.rst"), "custom lines {{ day_num }}").unwrap();
+        fs::write(dir.join("day_tokens.rst"), "tokens {{ day_num }}").unwrap();
+        let tera = load_templates(std::slice::from_ref(&dir)).unwrap();
+        assert_eq!(render_day(&tera, "lines", &tera_context).unwrap(), (String::from("custom lines 7"), None));
+        assert_eq!(render_day(&tera, "tokens", &tera_context).unwrap().0, "tokens 7");
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(matches!(load_templates(&[dir]), Err(GenerateError::Invalid(_))));
+    }
+}
@@ -1,62 +1,617 @@
-use std::{fmt::Display, collections::HashMap};
+use std::{fmt::Display, collections::HashMap, error::Error, ops::RangeInclusive, panic::{self, AssertUnwindSafe}, str::FromStr};
+use std::sync::{Mutex, atomic::{AtomicUsize, Ordering}};
+use std::thread;
+use std::time::{Duration, Instant};
 
-use chrono::{Utc, Duration};
+use crate::limits::Limits;
+use crate::worker::{self, WorkerCommand};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    Parse,
+    InvalidInput,
+    Unimplemented,
+    Timeout,
+    ResourceExceeded,
+    NotFound,
+    Load,
+    /// The solve worker process could not be started
+    Worker,
+    Panic,
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ErrorKind::Parse => "Parse",
+            ErrorKind::InvalidInput => "InvalidInput",
+            ErrorKind::Unimplemented => "Unimplemented",
+            ErrorKind::Timeout => "Timeout",
+            ErrorKind::ResourceExceeded => "ResourceExceeded",
+            ErrorKind::NotFound => "NotFound",
+            ErrorKind::Load => "Load",
+            ErrorKind::Worker => "Worker",
+            ErrorKind::Panic => "Panic",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for ErrorKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let kinds = [
+            ErrorKind::Parse, ErrorKind::InvalidInput, ErrorKind::Unimplemented, ErrorKind::Timeout,
+            ErrorKind::ResourceExceeded, ErrorKind::NotFound, ErrorKind::Load, ErrorKind::Worker, ErrorKind::Panic,
+        ];
+        kinds.iter().copied().find(|kind| kind.to_string() == s).ok_or_else(|| format!("unknown error kind '{}'", s))
+    }
+}
+
+/// 1-based position in the puzzle input an error refers to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: Option<usize>,
+}
 
 #[derive(Debug)]
-pub struct SolveError(pub String);
+pub struct SolveError {
+    pub kind: ErrorKind,
+    pub message: String,
+    pub location: Option<Location>,
+    source: Option<Box<dyn Error + Send + Sync>>,
+}
+
+impl SolveError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> SolveError {
+        SolveError { kind, message: message.into(), location: None, source: None }
+    }
+
+    pub fn unimplemented(day: &str, part: Part) -> SolveError {
+        SolveError::new(ErrorKind::Unimplemented, format!("day {} has no challenge {}", day, part))
+    }
+
+    /// Turns a caught panic payload into an error, `todo!` and `unimplemented!` count as unimplemented
+    pub fn from_panic(payload: Box<dyn std::any::Any + Send>) -> SolveError {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            String::from("handler panicked")
+        };
+
+        if message.starts_with("not yet implemented") || message.starts_with("not implemented") {
+            let detail = message.split_once(": ").map(|(_, detail)| detail).unwrap_or(&message);
+            SolveError::new(ErrorKind::Unimplemented, detail)
+        } else {
+            SolveError::new(ErrorKind::Panic, message)
+        }
+    }
+
+    /// Parse failure of a 0-based line index as handed out by `enumerate`
+    pub fn parse_line(line_idx: usize, source: impl Error + Send + Sync + 'static) -> SolveError {
+        SolveError::new(ErrorKind::Parse, source.to_string()).at_line(line_idx + 1).with_source(source)
+    }
+
+    pub fn with_source(mut self, source: impl Error + Send + Sync + 'static) -> SolveError {
+        self.source = Some(Box::new(source));
+        self
+    }
+
+    pub fn at_line(mut self, line: usize) -> SolveError {
+        self.location = Some(Location { line, column: None });
+        self
+    }
+
+    pub fn at(mut self, line: usize, column: usize) -> SolveError {
+        self.location = Some(Location { line, column: Some(column) });
+        self
+    }
+}
 
 impl Display for SolveError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "SolveError: {}", self.0)
+        write!(f, "{}: {}", self.kind, self.message)?;
+        match self.location {
+            Some(Location { line, column: Some(column) }) => write!(f, " (line {}, column {})", line, column),
+            Some(Location { line, column: None }) => write!(f, " (line {})", line),
+            None => Ok(()),
+        }
     }
 }
 
-pub struct DayHandler<'a, InputType>(Box<dyn AdventSolution<InputType> + 'a>);
+impl Error for SolveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_ref().map(|source| source.as_ref() as &(dyn Error + 'static))
+    }
+}
+
+/// Which challenge of a day to run. Most days only have `One` and `Two`, `Bonus` leaves room for extras.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+    Bonus(u8),
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().parse::<u8>() {
+            Ok(1) => Ok(Part::One),
+            Ok(2) => Ok(Part::Two),
+            Ok(n) if n > 2 => Ok(Part::Bonus(n)),
+            _ => Err(format!("invalid challenge '{}', expected a positive integer", s)),
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => f.pad("1"),
+            Part::Two => f.pad("2"),
+            Part::Bonus(n) => f.pad(&n.to_string()),
+        }
+    }
+}
+
+/// Monotonic timings of a single solve
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Timing {
+    /// Only set by handlers that parse their input separately from solving, on the first part solved from the parse
+    pub parse: Option<Duration>,
+    pub solve: Duration,
+}
+
+impl Timing {
+    pub fn total(&self) -> Duration {
+        self.parse.unwrap_or_default() + self.solve
+    }
+}
+
+/// Handlers are `Send + Sync` so independent challenges can be solved on worker threads
+pub struct DayHandler<'a, InputType>(Box<dyn AdventSolution<InputType> + Send + Sync + 'a>);
 
 impl<'a, InputType> DayHandler<'a, InputType> {
-    pub fn new(handler: impl AdventSolution<InputType> + 'a) -> DayHandler<'a, InputType> {
+    pub fn new(handler: impl AdventSolution<InputType> + Send + Sync + 'a) -> DayHandler<'a, InputType> {
         DayHandler(Box::new(handler))
     }
 }
 
+impl<'a> DayHandler<'a, &'a str> {
+    pub fn parsed(handler: impl ParsedSolution + Send + Sync + 'a) -> DayHandler<'a, &'a str> {
+        DayHandler::new(ParsedAdapter(handler))
+    }
+}
+
 impl<'a, InputType> AdventSolution<InputType> for DayHandler<'a, InputType> {
     fn get_day(&self) -> String { self.0.get_day()}
-    fn solve(&self, problem: &str, input: InputType) -> Result<String, SolveError> { self.0.solve(problem, input) }
+    fn parts(&self) -> Vec<Part> { self.0.parts() }
+    fn solve(&self, part: Part, input: InputType) -> Result<String, SolveError> { self.0.solve(part, input) }
+    fn solve_timed(&self, part: Part, input: InputType) -> (Timing, Result<String, SolveError>) { self.0.solve_timed(part, input) }
+    fn solve_parts(&self, parts: &[Part], input: InputType) -> Vec<TimedSolution> where InputType: Copy { self.0.solve_parts(parts, input) }
 }
 
 pub trait AdventSolution<InputType> {
     fn get_day(&self) -> String;
-    fn solve(&self, problem: &str, input: InputType) -> Result<String, SolveError>;
+    /// Parts run by `-c all`, override when a day has bonus parts
+    fn parts(&self) -> Vec<Part> { vec![Part::One, Part::Two] }
+    fn solve(&self, part: Part, input: InputType) -> Result<String, SolveError>;
+    /// Override to report parse and solve time separately
+    fn solve_timed(&self, part: Part, input: InputType) -> (Timing, Result<String, SolveError>) {
+        let start = Instant::now();
+        let solution = self.solve(part, input);
+
+        (Timing { parse: None, solve: start.elapsed() }, solution)
+    }
+    /// Solves several parts of the same input, a panic only fails the part it happened in
+    fn solve_parts(&self, parts: &[Part], input: InputType) -> Vec<TimedSolution> where InputType: Copy {
+        parts.iter().map(|part| catch_solve(|| self.solve_timed(*part, input))).collect()
+    }
+}
+
+/// Runs a solve, turning a panic into an error timed up to the panic
+fn catch_solve(solve: impl FnOnce() -> TimedSolution) -> TimedSolution {
+    let start = Instant::now();
+    panic::catch_unwind(AssertUnwindSafe(solve))
+        .unwrap_or_else(|payload| (Timing { parse: None, solve: start.elapsed() }, Err(SolveError::from_panic(payload))))
+}
+
+/// Days that parse their input once into `Parsed` and solve every part from it
+pub trait ParsedSolution {
+    type Parsed;
+
+    fn get_day(&self) -> String;
+    fn parts(&self) -> Vec<Part> { vec![Part::One, Part::Two] }
+    fn parse(&self, input: &str) -> Result<Self::Parsed, SolveError>;
+    fn solve_parsed(&self, part: Part, parsed: &Self::Parsed) -> Result<String, SolveError>;
+}
+
+/// Runs a `ParsedSolution` as an `AdventSolution`, timing the parse separately
+pub struct ParsedAdapter<S>(pub S);
+
+impl<'i, S: ParsedSolution> AdventSolution<&'i str> for ParsedAdapter<S> {
+    fn get_day(&self) -> String { self.0.get_day() }
+    fn parts(&self) -> Vec<Part> { self.0.parts() }
+    fn solve(&self, part: Part, input: &'i str) -> Result<String, SolveError> {
+        let parsed = self.0.parse(input)?;
+        self.0.solve_parsed(part, &parsed)
+    }
+    fn solve_timed(&self, part: Part, input: &'i str) -> (Timing, Result<String, SolveError>) {
+        let parse_start = Instant::now();
+        let parsed_res = self.0.parse(input);
+        let parse = Some(parse_start.elapsed());
+        let parsed = match parsed_res {
+            Ok(parsed) => parsed,
+            Err(e) => return (Timing { parse, solve: Duration::ZERO }, Err(e)),
+        };
+
+        let solve_start = Instant::now();
+        let solution = self.0.solve_parsed(part, &parsed);
+
+        (Timing { parse, solve: solve_start.elapsed() }, solution)
+    }
+    /// Parses once and solves every part from it, the parse time is reported on the first part
+    fn solve_parts(&self, parts: &[Part], input: &'i str) -> Vec<TimedSolution> {
+        let parse_start = Instant::now();
+        let parsed_res = panic::catch_unwind(AssertUnwindSafe(|| self.0.parse(input))).unwrap_or_else(|payload| Err(SolveError::from_panic(payload)));
+        let mut parse = Some(parse_start.elapsed());
+        let parsed = match parsed_res {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                // the first part keeps the original error with its source, the others get a copy without it
+                let copies: Vec<SolveError> = parts.iter().skip(1)
+                    .map(|_| SolveError { kind: e.kind, message: e.message.clone(), location: e.location, source: None })
+                    .collect();
+                return parts.first().map(|_| e).into_iter().chain(copies)
+                    .map(|error| (Timing { parse: parse.take(), solve: Duration::ZERO }, Err(error)))
+                    .collect();
+            },
+        };
+
+        parts.iter().map(|part| {
+            let (timing, solution) = catch_solve(|| {
+                let solve_start = Instant::now();
+                let solution = self.0.solve_parsed(*part, &parsed);
+                (Timing { parse: None, solve: solve_start.elapsed() }, solution)
+            });
+            (Timing { parse: parse.take(), ..timing }, solution)
+        }).collect()
+    }
 }
 
+pub type TimedSolution = (Timing, Result<String, SolveError>);
 
 pub struct SolutionHandler<'a, InputType> {
-    handlers: HashMap<String, DayHandler<'a, InputType>>,
+    handlers: HashMap<(String, String), DayHandler<'a, InputType>>,
+    /// Started for limited solves, the running executable when `None`
+    worker: Option<WorkerCommand>,
 }
 
 impl<'a, InputType> SolutionHandler<'a, InputType> {
-    pub fn new() -> SolutionHandler<'a, InputType> { SolutionHandler {handlers: HashMap::new()} }
+    pub fn new() -> SolutionHandler<'a, InputType> { SolutionHandler {handlers: HashMap::new(), worker: None} }
+
+    #[cfg(test)]
+    pub fn with_worker(mut self, command: WorkerCommand) -> SolutionHandler<'a, InputType> {
+        self.worker = Some(command);
+        self
+    }
 
-    pub fn register(&mut self, handlers: Vec<DayHandler<'a, InputType>>) {
+    pub fn register(&mut self, year: &str, handlers: Vec<DayHandler<'a, InputType>>) {
         for handler in handlers {
             let handler_day = handler.get_day();
-            self.handlers.insert(handler_day, handler);
+            self.handlers.insert((year.to_string(), handler_day), handler);
         }
     }
 
-    pub fn solve(&self, day: &str, problem: &str, input: InputType) -> (Duration, Result<String, SolveError>) {
-        let handler_opt = self.handlers.get(day);
-        if let Some(handler) = handler_opt {
-            let perf_start_time = Utc::now().time();
-            let solution = handler.solve(problem, input);
-            let perf_end_time = Utc::now().time();
-            let diff = perf_end_time - perf_start_time;
+    /// Registered years in order
+    #[cfg(test)]
+    pub fn years(&self) -> Vec<String> {
+        let mut years: Vec<String> = self.handlers.keys().map(|(year, _)| year.clone()).collect();
+        years.sort_unstable();
+        years.dedup();
+
+        years
+    }
+
+    /// Registered days of `year` within `days`, in numeric order
+    pub fn days(&self, year: &str, days: &RangeInclusive<usize>) -> Vec<String> {
+        let mut matching: Vec<usize> = self.handlers.keys()
+            .filter(|(handler_year, _)| handler_year == year)
+            .filter_map(|(_, day)| day.parse::<usize>().ok())
+            .filter(|day| days.contains(day))
+            .collect();
+        matching.sort_unstable();
+
+        matching.iter().map(|day| day.to_string()).collect()
+    }
+
+    pub fn parts(&self, year: &str, day: &str) -> Vec<Part> {
+        self.handlers.get(&(year.to_string(), day.to_string()))
+            .map(|handler| handler.parts())
+            .unwrap_or_default()
+    }
 
-            (diff, solution)
+    pub fn solve(&self, year: &str, day: &str, part: Part, input: InputType) -> (Timing, Result<String, SolveError>) {
+        let handler_opt = self.handlers.get(&(year.to_string(), day.to_string()));
+        if let Some(handler) = handler_opt {
+            catch_solve(|| handler.solve_timed(part, input))
         } else {
-            (Duration::milliseconds(1), Err(SolveError(format!("NotFound: day {}, problem {}", day, problem))))
+            (Timing::default(), Err(SolveError::new(ErrorKind::NotFound, format!("year {}, day {}, problem {}", year, day, part))))
+        }
+    }
+
+    /// Solves every part in `parts` from the same input, handlers that parse separately parse it once
+    pub fn solve_parts(&self, year: &str, day: &str, parts: &[Part], input: InputType) -> Vec<TimedSolution> where InputType: Copy {
+        match self.handlers.get(&(year.to_string(), day.to_string())) {
+            Some(handler) => handler.solve_parts(parts, input),
+            None => parts.iter().map(|part| self.solve(year, day, *part, input)).collect(),
         }
     }
 }
 
+impl<'a> SolutionHandler<'a, &'a str> {
+    /// Solves each part in its own worker process that is killed once it goes over a limit,
+    /// so a part that runs over does not take the others down with it
+    pub fn solve_limited(&self, year: &str, day: &str, parts: &[Part], input: &'a str, limits: &Limits) -> Vec<TimedSolution> {
+        // a worker solves in process, so it never starts workers of its own
+        if limits.is_unlimited() || worker::is_worker() || !self.handlers.contains_key(&(year.to_string(), day.to_string())) {
+            return self.solve_parts(year, day, parts, input);
+        }
+        match self.worker.clone().map(Ok).unwrap_or_else(WorkerCommand::current_exe) {
+            Ok(command) => parts.iter().flat_map(|part| worker::solve(&command, year, day, &[*part], input, limits)).collect(),
+            Err(e) => {
+                let message = format!("could not find the executable to start a solve worker: {}", e);
+                parts.iter().map(|_| (Timing::default(), Err(SolveError::new(ErrorKind::Worker, message.clone())))).collect()
+            },
+        }
+    }
+
+    /// Solves `(day, parts, input)` jobs on up to `workers` threads, one result per part in job order
+    pub fn solve_jobs(&self, year: &str, jobs: &[(String, Vec<Part>, &'a str)], workers: usize, limits: &Limits) -> Vec<TimedSolution> {
+        if workers <= 1 {
+            return jobs.iter().flat_map(|(day, parts, input)| self.solve_limited(year, day, parts, input, limits)).collect();
+        }
+
+        let next_job = AtomicUsize::new(0);
+        let results: Vec<Mutex<Option<Vec<TimedSolution>>>> = jobs.iter().map(|_| Mutex::new(None)).collect();
+        thread::scope(|scope| {
+            for _ in 0..workers.min(jobs.len()) {
+                scope.spawn(|| loop {
+                    let job_idx = next_job.fetch_add(1, Ordering::Relaxed);
+                    let Some((day, parts, input)) = jobs.get(job_idx) else { break };
+                    let solutions = self.solve_limited(year, day, parts, input, limits);
+                    *results[job_idx].lock().unwrap() = Some(solutions);
+                });
+            }
+        });
+
+        results.into_iter()
+            .flat_map(|result| result.into_inner().unwrap().expect("every job is solved before the scope ends"))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, error::Error};
+    use std::sync::{Arc, atomic::{AtomicUsize, Ordering}};
+    use std::time::{Duration, Instant};
+
+    use crate::limits::Limits;
+    use crate::worker::{self, WorkerCommand};
+    use super::{AdventSolution, DayHandler, ErrorKind, ParsedSolution, Part, SolutionHandler, SolveError};
+
+    #[test]
+    fn parse_part() {
+        assert_eq!("1".parse::<Part>(), Ok(Part::One));
+        assert_eq!("2".parse::<Part>(), Ok(Part::Two));
+        assert_eq!("7".parse::<Part>(), Ok(Part::Bonus(7)));
+        assert!("0".parse::<Part>().is_err());
+        assert!("one".parse::<Part>().is_err());
+    }
 
+    #[test]
+    fn unimplemented_part() {
+        let solution_handler = crate::registry::build();
+        let (_, res) = solution_handler.solve("2023", "1", Part::Bonus(7), "");
+        assert_eq!(res.unwrap_err().kind, ErrorKind::Unimplemented);
+    }
+
+    #[test]
+    fn panics_become_errors() {
+        struct Panicky {}
+        impl AdventSolution<&str> for Panicky {
+            fn get_day(&self) -> String { String::from("1") }
+            fn solve(&self, part: Part, _input: &str) -> Result<String, SolveError> {
+                match part {
+                    Part::One => panic!("bad input"),
+                    _ => todo!("part 2"),
+                }
+            }
+        }
+
+        let mut solution_handler = SolutionHandler::new();
+        solution_handler.register("1999", vec![DayHandler::new(Panicky {})]);
+        let (_, res) = solution_handler.solve("1999", "1", Part::One, "");
+        let error = res.unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Panic);
+        assert_eq!(error.message, "bad input");
+        let (_, res) = solution_handler.solve("1999", "1", Part::Two, "");
+        assert_eq!(res.unwrap_err().kind, ErrorKind::Unimplemented);
+    }
+
+    #[test]
+    fn registered_days() {
+        let solution_handler = crate::registry::build();
+        assert_eq!(solution_handler.days("2023", &(2..=4)), vec!["2", "3", "4"]);
+        assert_eq!(solution_handler.days("2021", &(9..=11)), vec!["9", "10", "11"]);
+        assert!(solution_handler.days("1999", &(1..=25)).is_empty());
+    }
+
+    #[test]
+    fn error_location_and_source() {
+        let parse_error = "x".parse::<u32>().unwrap_err();
+        let error = SolveError::parse_line(2, parse_error);
+        assert_eq!(error.kind, ErrorKind::Parse);
+        assert_eq!(error.to_string(), "Parse: invalid digit found in string (line 3)");
+        assert!(error.source().is_some());
+    }
+
+    #[test]
+    fn parsed_solutions_time_parsing() {
+        struct Sum {}
+        impl ParsedSolution for Sum {
+            type Parsed = Vec<u32>;
+            fn get_day(&self) -> String { String::from("1") }
+            fn parse(&self, input: &str) -> Result<Vec<u32>, SolveError> {
+                input.lines().enumerate()
+                    .map(|(line_idx, line)| line.parse().map_err(|e| SolveError::parse_line(line_idx, e)))
+                    .collect()
+            }
+            fn solve_parsed(&self, part: Part, parsed: &Vec<u32>) -> Result<String, SolveError> {
+                match part {
+                    Part::One => Ok(parsed.iter().sum::<u32>().to_string()),
+                    _ => Ok(parsed.iter().max().copied().unwrap_or_default().to_string()),
+                }
+            }
+        }
+
+        let input = "3\n4";
+        let bad_input = "3\nx";
+        let handler = DayHandler::parsed(Sum {});
+        let (timing, res) = handler.solve_timed(Part::One, input);
+        assert!(timing.parse.is_some());
+        assert_eq!(res.unwrap(), "7");
+        assert_eq!(handler.solve(Part::Two, input).unwrap(), "4");
+        let (timing, res) = handler.solve_timed(Part::One, bad_input);
+        assert!(timing.parse.is_some());
+        assert_eq!(res.unwrap_err().location.map(|location| location.line), Some(2));
+
+        let results = handler.solve_parts(&[Part::One, Part::Two], bad_input);
+        let errors: Vec<&SolveError> = results.iter().map(|(_, res)| res.as_ref().unwrap_err()).collect();
+        assert!(errors[0].source().is_some());
+        assert_eq!(errors[1].location, errors[0].location);
+        assert_eq!(errors[1].message, errors[0].message);
+    }
+
+    #[test]
+    fn all_parts_share_one_parse() {
+        struct Counted(Arc<AtomicUsize>);
+        impl ParsedSolution for Counted {
+            type Parsed = usize;
+            fn get_day(&self) -> String { String::from("1") }
+            fn parse(&self, input: &str) -> Result<usize, SolveError> {
+                self.0.fetch_add(1, Ordering::Relaxed);
+                Ok(input.len())
+            }
+            fn solve_parsed(&self, part: Part, parsed: &usize) -> Result<String, SolveError> {
+                match part {
+                    Part::Two => panic!("part 2"),
+                    _ => Ok(parsed.to_string()),
+                }
+            }
+        }
+
+        let parses = Arc::new(AtomicUsize::new(0));
+        let mut solution_handler = SolutionHandler::new();
+        solution_handler.register("2022", vec![DayHandler::parsed(Counted(Arc::clone(&parses)))]);
+        // `-c all` runs as one job per day input
+        let jobs = vec![(String::from("1"), solution_handler.parts("2022", "1"), "abc")];
+        let results = solution_handler.solve_jobs("2022", &jobs, 1, &Limits::default());
+        assert_eq!(parses.load(Ordering::Relaxed), 1);
+        assert_eq!(results.len(), 2);
+        assert!(results[0].0.parse.is_some());
+        assert_eq!(results[0].1.as_ref().unwrap(), "3");
+        assert_eq!(results[1].0.parse, None);
+        assert_eq!(results[1].1.as_ref().unwrap_err().kind, ErrorKind::Panic);
+        solution_handler.solve_jobs("2022", &jobs, 2, &Limits::default());
+        assert_eq!(parses.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn jobs_keep_order() {
+        let input = "";
+        let solution_handler = crate::registry::build();
+        let jobs: Vec<(String, Vec<Part>, &str)> = vec![
+            (String::from("1"), vec![Part::One], input),
+            (String::from("1"), vec![Part::Bonus(3), Part::Two], input),
+            (String::from("1"), vec![Part::Bonus(4)], input),
+        ];
+        let results = solution_handler.solve_jobs("2023", &jobs, 3, &Limits::default());
+        let kinds: Vec<Option<ErrorKind>> = results.iter().map(|(_, res)| res.as_ref().err().map(|e| e.kind)).collect();
+        assert_eq!(kinds.len(), 4);
+        assert_eq!(kinds[1], Some(ErrorKind::Unimplemented));
+        assert_eq!(kinds[3], Some(ErrorKind::Unimplemented));
+        assert!(results[1].1.as_ref().unwrap_err().message.contains("challenge 3"));
+        assert!(results[3].1.as_ref().unwrap_err().message.contains("challenge 4"));
+    }
+
+    fn greedy_handler() -> SolutionHandler<'static, &'static str> {
+        struct Greedy {}
+        impl AdventSolution<&str> for Greedy {
+            fn get_day(&self) -> String { String::from("1") }
+            fn parts(&self) -> Vec<Part> { vec![Part::One, Part::Two, Part::Bonus(3), Part::Bonus(4)] }
+            fn solve(&self, part: Part, input: &str) -> Result<String, SolveError> {
+                let mut held: Vec<Vec<u8>> = vec![];
+                match part {
+                    Part::Bonus(3) => return Ok(input.to_uppercase()),
+                    Part::Bonus(_) => return Err(SolveError::new(ErrorKind::InvalidInput, "no fours").at(2, 5)),
+                    _ => {},
+                }
+                for _ in 0..5000 {
+                    if part == Part::Two && held.len() < 64 {
+                        held.push(vec![1; 1 << 20]);
+                    }
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+                Ok(held.len().to_string())
+            }
+        }
+
+        let mut solution_handler = SolutionHandler::new().with_worker(WorkerCommand {
+            program: env::current_exe().unwrap(),
+            args: vec![String::from("handler::tests::limit_worker"), String::from("--exact"), String::from("--quiet")],
+        });
+        solution_handler.register("2023", vec![DayHandler::new(Greedy {})]);
+
+        solution_handler
+    }
+
+    /// Solve worker of `limits_kill_solves`, started as its own test process
+    #[test]
+    fn limit_worker() {
+        if worker::is_worker() {
+            worker::serve(&greedy_handler());
+        }
+    }
+
+    #[test]
+    fn limits_kill_solves() {
+        let solution_handler = greedy_handler();
+        let start = Instant::now();
+        let timeout = Limits { timeout: Some(Duration::from_millis(500)), memory: None };
+        let solutions = solution_handler.solve_limited("2023", "1", &[Part::Bonus(3), Part::One], "a", &timeout);
+        assert_eq!(solutions[0].1.as_ref().unwrap(), "A");
+        assert_eq!(solutions[1].1.as_ref().unwrap_err().kind, ErrorKind::Timeout);
+        let memory = Limits { timeout: Some(Duration::from_secs(10)), memory: Some(16 << 20) };
+        let solutions = solution_handler.solve_limited("2023", "1", &[Part::Two, Part::Bonus(3)], "b", &memory);
+        assert_eq!(solutions[0].1.as_ref().unwrap_err().kind, ErrorKind::ResourceExceeded);
+        assert_eq!(solutions[1].1.as_ref().unwrap(), "B");
+        assert!(start.elapsed() < Duration::from_secs(5), "{:?}", start.elapsed());
+
+        let solutions = solution_handler.solve_limited("2023", "1", &[Part::Bonus(3), Part::Bonus(4)], "abc\nd", &memory);
+        assert_eq!(solutions[0].1.as_ref().unwrap(), "ABC\nD");
+        let error = solutions[1].1.as_ref().unwrap_err();
+        assert_eq!(error.to_string(), "InvalidInput: no fours (line 2, column 5)");
+
+        let missing = greedy_handler().with_worker(WorkerCommand { program: env::temp_dir().join("advent-no-such-worker"), args: vec![] });
+        let (_, res) = missing.solve_limited("2023", "1", &[Part::Bonus(3)], "", &memory).remove(0);
+        assert_eq!(res.unwrap_err().kind, ErrorKind::Worker);
+    }
+}
@@ -0,0 +1,190 @@
+use std::{fs::{self, OpenOptions}, io::{self, Write}, path::Path, process::Command, time::Duration};
+
+use chrono::Utc;
+
+use crate::handler::Part;
+use crate::report::display_ms;
+
+const HEADER: &str = "date,commit,year,day,part,runs,parse_ns,solve_ns";
+
+/// One line of the timing history, durations are medians when `runs` is above 1
+#[derive(Clone, Debug, PartialEq)]
+pub struct HistoryEntry {
+    pub date: String,
+    pub commit: String,
+    pub year: String,
+    pub day: String,
+    pub part: Part,
+    pub runs: usize,
+    pub parse: Option<Duration>,
+    pub solve: Duration,
+}
+
+#[derive(Debug)]
+pub enum HistoryError {
+    ReadError(String),
+    WriteError(String),
+    Parse(String),
+}
+
+impl HistoryEntry {
+    /// `commit` is looked up once per run with `current_commit`
+    pub fn new(commit: &str, year: &str, day: &str, part: Part, runs: usize, parse: Option<Duration>, solve: Duration) -> HistoryEntry {
+        HistoryEntry {
+            date: Utc::now().to_rfc3339(),
+            commit: commit.to_string(),
+            year: year.to_string(),
+            day: day.to_string(),
+            part,
+            runs,
+            parse,
+            solve,
+        }
+    }
+
+    pub fn total(&self) -> Duration {
+        self.parse.unwrap_or_default() + self.solve
+    }
+
+    fn to_csv(&self) -> String {
+        format!("{},{},{},{},{},{},{},{}",
+            self.date, self.commit, self.year, self.day, self.part, self.runs,
+            self.parse.map(|parse| parse.as_nanos().to_string()).unwrap_or_default(),
+            self.solve.as_nanos())
+    }
+
+    fn from_csv(line_idx: usize, line: &str) -> Result<HistoryEntry, HistoryError> {
+        let invalid = |field: &str| HistoryError::Parse(format!("line {}: invalid {}", line_idx + 1, field));
+        let fields: Vec<&str> = line.split(',').collect();
+        if fields.len() != 8 {
+            return Err(HistoryError::Parse(format!("line {}: expected 8 fields, found {}", line_idx + 1, fields.len())));
+        }
+        let parse_nanos = |value: &str, field: &str| value.parse::<u64>().map(Duration::from_nanos).map_err(|_| invalid(field));
+
+        Ok(HistoryEntry {
+            date: fields[0].to_string(),
+            commit: fields[1].to_string(),
+            year: fields[2].to_string(),
+            day: fields[3].to_string(),
+            part: fields[4].parse().map_err(|_| invalid("part"))?,
+            runs: fields[5].parse().map_err(|_| invalid("runs"))?,
+            parse: if fields[6].is_empty() { None } else { Some(parse_nanos(fields[6], "parse_ns")?) },
+            solve: parse_nanos(fields[7], "solve_ns")?,
+        })
+    }
+}
+
+/// Short hash of the checked out commit, `unknown` outside of a git checkout
+pub fn current_commit() -> String {
+    Command::new("git").args(["rev-parse", "--short", "HEAD"]).output().ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|commit| commit.trim().to_string())
+        .unwrap_or_else(|| String::from("unknown"))
+}
+
+/// Reads the history file, a missing file is an empty history
+pub fn load(path: &Path) -> Result<Vec<HistoryEntry>, HistoryError> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(HistoryError::ReadError(format!("{} - {}", e, path.display()))),
+    };
+
+    text.lines().enumerate()
+        .filter(|(_, line)| !line.is_empty() && *line != HEADER)
+        .map(|(line_idx, line)| HistoryEntry::from_csv(line_idx, line))
+        .collect()
+}
+
+pub fn append(path: &Path, entries: &[HistoryEntry]) -> Result<(), HistoryError> {
+    let write_error = |e: io::Error| HistoryError::WriteError(format!("{} - {}", e, path.display()));
+    let is_new = !path.exists();
+    let mut file = OpenOptions::new().create(true).append(true).open(path).map_err(write_error)?;
+    if is_new {
+        writeln!(file, "{}", HEADER).map_err(write_error)?;
+    }
+    for entry in entries {
+        writeln!(file, "{}", entry.to_csv()).map_err(write_error)?;
+    }
+
+    Ok(())
+}
+
+/// Most recent recorded entry for a challenge
+pub fn baseline<'h>(history: &'h [HistoryEntry], year: &str, day: &str, part: Part) -> Option<&'h HistoryEntry> {
+    history.iter().rev().find(|entry| entry.year == year && entry.day == day && entry.part == part)
+}
+
+/// Percentage the current timing changed against the baseline, positive is slower
+pub fn change_pct(baseline: &HistoryEntry, current: &HistoryEntry) -> f64 {
+    let baseline_secs = baseline.total().as_secs_f64();
+    if baseline_secs == 0. {
+        return 0.;
+    }
+
+    (current.total().as_secs_f64() - baseline_secs) / baseline_secs * 100.
+}
+
+/// Table of each entry against its baseline and whether any got slower than `threshold_pct`
+pub fn comparison(history: &[HistoryEntry], entries: &[HistoryEntry], threshold_pct: f64) -> (String, bool) {
+    let mut regressed = false;
+    let mut table = format!("{:<4} | {:<3} | {:<4} | {:>13} | {:>12} | {:>8} | Status\n", "Year", "Day", "Part", "Baseline (ms)", "Current (ms)", "Change");
+    for entry in entries {
+        match baseline(history, &entry.year, &entry.day, entry.part) {
+            Some(baseline) => {
+                let change = change_pct(baseline, entry);
+                let status = if change > threshold_pct {
+                    regressed = true;
+                    format!("REGRESSION (baseline {} on {})", baseline.commit, baseline.date)
+                } else {
+                    String::from("ok")
+                };
+                table.push_str(&format!("{:<4} | {:<3} | {:<4} | {:>13} | {:>12} | {:>7.1}% | {}\n",
+                    entry.year, entry.day, entry.part, display_ms(&baseline.total()), display_ms(&entry.total()), change, status));
+            },
+            None => table.push_str(&format!("{:<4} | {:<3} | {:<4} | {:>13} | {:>12} | {:>8} | no baseline\n",
+                entry.year, entry.day, entry.part, "-", display_ms(&entry.total()), "-")),
+        }
+    }
+
+    (table, regressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::handler::Part;
+    use super::{baseline, change_pct, HistoryEntry};
+
+    fn entry(commit: &str, solve_ms: u64) -> HistoryEntry {
+        HistoryEntry {
+            date: String::from("2023-12-05T00:00:00+00:00"),
+            commit: commit.to_string(),
+            year: String::from("2023"),
+            day: String::from("5"),
+            part: Part::Two,
+            runs: 10,
+            parse: Some(Duration::from_millis(1)),
+            solve: Duration::from_millis(solve_ms),
+        }
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let original = entry("abc1234", 9);
+        let parsed = HistoryEntry::from_csv(0, &original.to_csv()).unwrap();
+        assert_eq!(parsed, original);
+        assert!(HistoryEntry::from_csv(0, "not,enough").is_err());
+    }
+
+    #[test]
+    fn compare_against_latest() {
+        let history = vec![entry("old", 99), entry("new", 9)];
+        let latest = baseline(&history, "2023", "5", Part::Two).unwrap();
+        assert_eq!(latest.commit, "new");
+        assert!(baseline(&history, "2023", "5", Part::One).is_none());
+        assert!((change_pct(latest, &entry("current", 14)) - 50.).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,99 @@
+use std::{alloc::{GlobalAlloc, Layout, System}, cell::Cell, ptr, sync::{Arc, atomic::{AtomicIsize, Ordering}}, time::Duration};
+
+/// Per-solve limits, a solve over either is killed and reported as an error
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Limits {
+    pub timeout: Option<Duration>,
+    /// Soft ceiling in bytes, checked while the solve runs so it can briefly go over
+    pub memory: Option<usize>,
+}
+
+impl Limits {
+    pub fn is_unlimited(&self) -> bool {
+        self.timeout.is_none() && self.memory.is_none()
+    }
+}
+
+thread_local! {
+    static ALLOCATED: Cell<*const AtomicIsize> = const { Cell::new(ptr::null()) };
+}
+
+/// System allocator that counts live bytes allocated by threads inside an `AllocationScope`
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn track(delta: isize) {
+        // try_with as allocations still happen while thread locals are torn down
+        let _ = ALLOCATED.try_with(|allocated| {
+            let counter = allocated.get();
+            if !counter.is_null() {
+                // SAFETY: the scope that set the pointer keeps the counter alive until it clears it
+                unsafe { (*counter).fetch_add(delta, Ordering::Relaxed) };
+            }
+        });
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let allocation = System.alloc(layout);
+        if !allocation.is_null() {
+            CountingAllocator::track(layout.size() as isize);
+        }
+        allocation
+    }
+
+    unsafe fn dealloc(&self, allocation: *mut u8, layout: Layout) {
+        System.dealloc(allocation, layout);
+        CountingAllocator::track(-(layout.size() as isize));
+    }
+
+    unsafe fn realloc(&self, allocation: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let reallocation = System.realloc(allocation, layout, new_size);
+        if !reallocation.is_null() {
+            CountingAllocator::track(new_size as isize - layout.size() as isize);
+        }
+        reallocation
+    }
+}
+
+/// Counts the current thread's allocations into `counter` until dropped
+pub struct AllocationScope {
+    _counter: Arc<AtomicIsize>,
+}
+
+impl AllocationScope {
+    pub fn enter(counter: Arc<AtomicIsize>) -> AllocationScope {
+        ALLOCATED.with(|allocated| allocated.set(Arc::as_ptr(&counter)));
+        AllocationScope { _counter: counter }
+    }
+}
+
+impl Drop for AllocationScope {
+    fn drop(&mut self) {
+        let _ = ALLOCATED.try_with(|allocated| allocated.set(ptr::null()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, atomic::{AtomicIsize, Ordering}};
+
+    use super::AllocationScope;
+
+    #[test]
+    fn counts_scoped_allocations() {
+        let counter = Arc::new(AtomicIsize::new(0));
+        let kept = {
+            let _scope = AllocationScope::enter(Arc::clone(&counter));
+            let kept: Vec<u8> = Vec::with_capacity(1 << 20);
+            let _dropped: Vec<u8> = Vec::with_capacity(1 << 20);
+            kept
+        };
+        assert!(counter.load(Ordering::Relaxed) >= 1 << 20);
+        assert!(counter.load(Ordering::Relaxed) < 2 << 20);
+        let _unscoped: Vec<u8> = Vec::with_capacity(1 << 20);
+        assert!(counter.load(Ordering::Relaxed) < 2 << 20);
+        drop(kept);
+    }
+}
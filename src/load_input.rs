@@ -1,32 +1,60 @@
-use std::fs;
-use std::path::PathBuf;
+use std::{fs, io::{self, Read}};
+use std::path::{Path, PathBuf};
 use reqwest::StatusCode;
 
+use crate::client::AocClient;
+
 #[derive(Debug)]
 pub enum LoadError {
-    HttpError(String),
+    /// `status` is `None` when the request failed before a response came back
+    HttpError { status: Option<StatusCode>, reason: String },
     ReadError(String),
     WriteError(String),
+    MissingSession(String),
+    NotUnlocked(String),
 }
 
 impl From<reqwest::Error> for LoadError {
     fn from(e: reqwest::Error) -> Self {
-        LoadError::HttpError(e.to_string())
+        LoadError::HttpError { status: e.status(), reason: e.to_string() }
     }
 }
 
+/// Bodies the server answers with instead of an input, checked as prefixes
+const ERROR_BODIES: [&str; 4] = [
+    "Please don't repeatedly request this endpoint before it unlocks",
+    "Puzzle inputs differ by user",
+    "Please log in",
+    "404 Not Found",
+];
+
+/// Starts of a page served instead of an input, compared lowercased
+const HTML_MARKERS: [&str; 3] = ["<!doctype", "<html", "<?xml"];
+
+/// Input a day is solved with, `name` is only set for inputs given on the command line
+pub struct DayInput {
+    pub day: String,
+    pub name: Option<String>,
+    pub text: Result<&'static str, Vec<LoadError>>,
+}
 
-pub async fn load<'a>(day_num: &str, year: &str, session: &str, allow_remote: bool, base_path_opt: Option<&str>) -> Result<String, Vec<LoadError>> {
+/// Directory holding a day's module, input and answers
+pub fn day_path(day_num: &str, year: &str, base_path_opt: Option<&str>) -> PathBuf {
     let base_path = base_path_opt.unwrap_or("./src");
-    let path: PathBuf = [base_path, &format!("year{}", year), &format!("day{}", day_num), "input"].iter().collect();
+    [base_path, &format!("year{}", year), &format!("day{}", day_num)].iter().collect()
+}
+
+/// Reads the day's input file, fetching and saving it with `client_opt` when it is missing
+pub async fn load(day_num: &str, year: &str, client_opt: Option<&AocClient>, base_path_opt: Option<&str>) -> Result<String, Vec<LoadError>> {
+    let path = day_path(day_num, year, base_path_opt).join("input");
     let local_read = load_local(&path).await;
     if let Ok(text) = local_read {
         return Ok(text);
     }
-    println!("WARN: Local load failure: {:?}", local_read.as_ref().unwrap_err());
+    eprintln!("WARN: Local load failure: {:?}", local_read.as_ref().unwrap_err());
 
-    if allow_remote {
-        let remote_read = load_remote(day_num, &path, session).await;
+    if let Some(client) = client_opt {
+        let remote_read = load_remote(client, day_num, year, &path).await;
         if let Ok(text) = remote_read {
             return Ok(text);
         }
@@ -37,19 +65,181 @@ pub async fn load<'a>(day_num: &str, year: &str, session: &str, allow_remote: bo
     Err(vec![local_read.unwrap_err()])
 }
 
-pub async fn load_local(path: &PathBuf) -> Result<String, LoadError> {
+/// Sample input file of a day, `example` for the first sample, then `example2`, `example3`, ..
+pub fn example_name(example_num: usize) -> String {
+    if example_num == 1 { String::from("example") } else { format!("example{}", example_num) }
+}
+
+/// Reads a sample input saved next to the day's input
+pub async fn load_example(day_num: &str, year: &str, example_num: usize, base_path_opt: Option<&str>) -> Result<String, LoadError> {
+    load_local(&day_path(day_num, year, base_path_opt).join(example_name(example_num))).await
+}
+
+/// Inputs given on the command line: a file, `-` for stdin, or a directory of input files, named by file name
+pub fn load_override(path_arg: &str) -> Result<Vec<(String, String)>, LoadError> {
+    if path_arg == "-" {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text).map_err(|e| LoadError::ReadError(format!("{} - stdin", e)))?;
+        return Ok(vec![(String::from("stdin"), text)]);
+    }
+
+    let path = Path::new(path_arg);
+    let read = |path: &Path| fs::read_to_string(path).map_err(|e| LoadError::ReadError(format!("{} - {}", e, path.display())));
+    let name = |path: &Path| path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_else(|| path_arg.to_string());
+    if !path.is_dir() {
+        return Ok(vec![(name(path), read(path)?)]);
+    }
+
+    let mut files: Vec<PathBuf> = fs::read_dir(path)
+        .map_err(|e| LoadError::ReadError(format!("{} - {}", e, path.display())))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|file| file.is_file() && !name(file).starts_with('.'))
+        .collect();
+    if files.is_empty() {
+        return Err(LoadError::ReadError(format!("no input files in {}", path.display())));
+    }
+    files.sort();
+
+    files.iter().map(|file| Ok((name(file), read(file)?))).collect()
+}
+
+pub async fn load_local(path: &Path) -> Result<String, LoadError> {
     fs::read_to_string(path)
-        .map_err(|e| LoadError::ReadError(format!("{} - {}", e.to_string(), path.display())))
+        .map_err(|e| LoadError::ReadError(format!("{} - {}", e, path.display())))
 }
 
-async fn load_remote(day_num: &str, path: &PathBuf, session: &str) -> Result<String, LoadError> {
-    let url = format!("https://adventofcode.com/2023/day/{}/input", day_num);
-    let client = reqwest::Client::new();
-    let request = client.get(url).header("Cookie", session).build()?;
-    let response = client.execute(request).await
-        .map_err(|e| LoadError::HttpError(e.status().unwrap_or(StatusCode::default()).to_string()))?;
-    let text = response.text().await.map_err(|e| LoadError::HttpError(e.to_string()))?;
-    fs::write(&path, &text).map_err(|e| LoadError::WriteError(e.to_string()))?;
+async fn load_remote(client: &AocClient, day_num: &str, year: &str, path: &Path) -> Result<String, LoadError> {
+    let text = client.input(year, day_num, check_input).await?;
+    write_atomic(path, &text)?;
 
     Ok(text)
 }
+
+/// Rejects the known bodies that are not puzzle input, inputs can start with `<` so only html markers count as a page
+fn check_input(text: &str) -> Result<(), String> {
+    let first_line = text.trim().lines().next().unwrap_or_default().trim();
+    let lowercase = first_line.to_lowercase();
+    let is_html = HTML_MARKERS.iter().any(|marker| lowercase.starts_with(marker));
+    if text.trim().is_empty() {
+        Err(String::from("empty response"))
+    } else if ERROR_BODIES.iter().any(|body| first_line.starts_with(body)) || is_html {
+        Err(first_line.to_string())
+    } else {
+        Ok(())
+    }
+}
+
+/// Writes next to `path` and renames over it, so a failed write never leaves a partial input
+pub fn write_atomic(path: &Path, text: &str) -> Result<(), LoadError> {
+    let write_error = |e: io::Error| LoadError::WriteError(format!("{} - {}", e, path.display()));
+    let temp_path = path.with_extension("download");
+    fs::write(&temp_path, text)
+        .and_then(|_| fs::rename(&temp_path, path))
+        .map_err(|e| {
+            let _ = fs::remove_file(&temp_path);
+            write_error(e)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, time::Duration};
+
+    use crate::client::{tests::serve_once, AocClient};
+    use super::{load, load_example, load_override, LoadError};
+
+    fn client(base_url: &str) -> AocClient {
+        AocClient::new(base_url).unwrap().with_cache_dir(None).with_min_delay(Duration::ZERO)
+    }
+
+    #[tokio::test]
+    async fn remote_fetch_uses_year() {
+        let base_path = env::temp_dir().join(format!("advent-remote-{}", std::process::id()));
+        fs::create_dir_all(base_path.join("year2021").join("day3")).unwrap();
+        let (base_url, server) = serve_once("200 OK", "00100\n11110\n");
+
+        let text = load("3", "2021", Some(&client(&base_url)), base_path.to_str()).await.unwrap();
+        let request = server.join().unwrap();
+        assert_eq!(text, "00100\n11110\n");
+        assert!(request.starts_with("GET /2021/day/3/input HTTP/1.1"), "{}", request);
+        assert!(request.to_lowercase().contains("cookie: session=abc123\r\n"), "{}", request);
+        assert_eq!(fs::read_to_string(base_path.join("year2021").join("day3").join("input")).unwrap(), text);
+
+        fs::remove_dir_all(&base_path).unwrap();
+    }
+
+    #[test]
+    fn override_file_or_directory() {
+        let dir = env::temp_dir().join(format!("advent-inputs-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("b-edge"), "2").unwrap();
+        fs::write(dir.join("a-colleague"), "1").unwrap();
+        fs::write(dir.join(".hidden"), "ignored").unwrap();
+
+        let named = load_override(dir.to_str().unwrap()).unwrap();
+        assert_eq!(named, vec![(String::from("a-colleague"), String::from("1")), (String::from("b-edge"), String::from("2"))]);
+        let single = load_override(dir.join("b-edge").to_str().unwrap()).unwrap();
+        assert_eq!(single, vec![(String::from("b-edge"), String::from("2"))]);
+        assert!(load_override(dir.join("missing").to_str().unwrap()).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn examples_by_number() {
+        let base_path = env::temp_dir().join(format!("advent-examples-{}", std::process::id()));
+        let day_path = base_path.join("year2023").join("day6");
+        fs::create_dir_all(&day_path).unwrap();
+        fs::write(day_path.join("example"), "first").unwrap();
+        fs::write(day_path.join("example2"), "second").unwrap();
+
+        assert_eq!(load_example("6", "2023", 1, base_path.to_str()).await.unwrap(), "first");
+        assert_eq!(load_example("6", "2023", 2, base_path.to_str()).await.unwrap(), "second");
+        assert!(matches!(load_example("6", "2023", 3, base_path.to_str()).await, Err(LoadError::ReadError(_))));
+
+        fs::remove_dir_all(&base_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn bracket_input_is_accepted() {
+        let base_path = env::temp_dir().join(format!("advent-remote-brackets-{}", std::process::id()));
+        fs::create_dir_all(base_path.join("year2021").join("day10")).unwrap();
+        let (base_url, server) = serve_once("200 OK", "<{([{{}}[<[[[<>{}]]]>[]]\n[({(<(())[]>[[{[]{<()<>>\n");
+
+        let text = load("10", "2021", Some(&client(&base_url)), base_path.to_str()).await.unwrap();
+        server.join().unwrap();
+        assert!(text.starts_with("<{(["));
+
+        fs::remove_dir_all(&base_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn remote_errors_leave_no_input() {
+        let base_path = env::temp_dir().join(format!("advent-remote-errors-{}", std::process::id()));
+        let day_path = base_path.join("year2021").join("day5");
+        fs::create_dir_all(&day_path).unwrap();
+        let responses = [
+            ("404 Not Found", "Please don't repeatedly request this endpoint before it unlocks! The calendar countdown is synchronized with the server time; the link will be enabled on the calendar the instant this puzzle becomes available.\n", Some(404)),
+            ("400 Bad Request", "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n", Some(400)),
+            ("200 OK", "Please log in to get your puzzle input.\n", Some(200)),
+            ("200 OK", "<!DOCTYPE html>\n<html lang=\"en-us\">\n", Some(200)),
+            ("200 OK", "<HTML><body>Not an input</body></HTML>\n", Some(200)),
+        ];
+
+        for (status, body, expected_status) in responses {
+            let (base_url, server) = serve_once(status, body);
+            let errors = load("5", "2021", Some(&client(&base_url)), base_path.to_str()).await.unwrap_err();
+            server.join().unwrap();
+            match &errors[1] {
+                LoadError::HttpError { status, reason } => {
+                    assert_eq!(status.map(|status| status.as_u16()), expected_status);
+                    assert!(body.starts_with(reason.as_str()), "{}", reason);
+                },
+                e => panic!("expected an http error, got {:?}", e),
+            }
+            assert_eq!(fs::read_dir(&day_path).unwrap().count(), 0);
+        }
+
+        fs::remove_dir_all(&base_path).unwrap();
+    }
+}
@@ -1,55 +1,329 @@
 #![feature(iter_array_chunks)]
+#[macro_use]
 extern crate pest_derive;
+mod answers;
+mod bench;
+mod client;
 mod cli;
 mod generate;
 mod handler;
+mod history;
+mod limits;
 mod load_input;
+mod puzzle;
+mod registry;
+mod report;
+mod session;
+mod submit;
+mod worker;
+mod year2021;
+mod year2022;
 mod year2023;
 
-use handler::SolutionHandler;
-use year2023::prelude::*;
+use std::{fs, panic, path::{Path, PathBuf}, process};
 
+use answers::{Answers, CheckStatus};
+use cli::PartSelector;
+use client::AocClient;
+use handler::{ErrorKind, Part, SolveError, Timing};
+use history::HistoryEntry;
+use load_input::DayInput;
+use report::{OutputFormat, SolveReport};
+use submit::{SubmitError, SubmitOutcome};
+
+#[global_allocator]
+static ALLOCATOR: limits::CountingAllocator = limits::CountingAllocator;
+
+/// Handler panics are reported as errors, keep the hook output to a single line
+fn set_panic_hook() {
+    panic::set_hook(Box::new(|info| eprintln!("WARN: {}", info.to_string().replace('\n', " "))));
+}
 
 #[tokio::main]
 async fn main() {
+    if worker::is_worker() {
+        set_panic_hook();
+        worker::serve(&registry::build());
+        return;
+    }
     let matches = cli::init();
     let day = matches.value_of("day").unwrap();
-    let year = matches.value_of("year").unwrap_or("2023");
+    let year = matches.value_of("year").unwrap_or(registry::DEFAULT_YEAR);
+    let base_url = matches.value_of("base-url").unwrap_or(client::DEFAULT_BASE_URL);
+    let mut examples = vec![];
+    if matches.is_present("fetch-puzzle") {
+        let fetch_day = match cli::single_day(&matches) {
+            Some(day_num) => day_num.to_string(),
+            None => {
+                eprintln!("Puzzle fetch failure: day must be a single day from 1 to 25, got {}", day);
+                process::exit(1);
+            },
+        };
+        let fetched = match AocClient::new(base_url) {
+            Ok(client) if matches.is_present("no-cache") => puzzle::fetch(&client.with_cache_dir(None), &fetch_day, year, None).await,
+            Ok(client) => puzzle::fetch(&client, &fetch_day, year, None).await,
+            Err(e) => Err(e),
+        };
+        match fetched {
+            Ok(puzzle) => {
+                let unlocked = if puzzle.parts < 2 { ", part 2 is not unlocked yet" } else { "" };
+                println!("Saved day {} puzzle with {} example(s){}", fetch_day, puzzle.examples.iter().map(Vec::len).sum::<usize>(), unlocked);
+                examples = puzzle.paired_examples();
+            },
+            Err(e) => {
+                eprintln!("Puzzle fetch failure: {:?}", e);
+                process::exit(1);
+            },
+        }
+        if !matches.is_present("generate") {
+            return;
+        }
+    }
     if matches.is_present("generate") {
-        match generate::generate_day(day, year) {
-            Ok(()) => {},
-            Err(e) => println!("{:?}", e)
+        // flags replace what the puzzle description gave
+        if let Some(example_path) = matches.value_of("example-input") {
+            match fs::read_to_string(example_path) {
+                Ok(input) => {
+                    if examples.is_empty() {
+                        examples.push(generate::Example::default());
+                    }
+                    examples[0].input = Some(input);
+                },
+                Err(e) => {
+                    eprintln!("Example load failure: {} - {}", e, example_path);
+                    process::exit(1);
+                },
+            }
+        }
+        // expected answers are all for the first example
+        if let Some(expected) = matches.values_of("expect") {
+            examples.truncate(1);
+            examples.resize_with(1, generate::Example::default);
+            examples[0].answers = expected.map(|answer| Some(answer.to_string())).collect();
+        }
+        let options = generate::GenerateOptions {
+            dry_run: matches.is_present("dry-run"),
+            force: matches.is_present("force"),
+            template: matches.value_of("template").unwrap_or(generate::DEFAULT_TEMPLATE).to_string(),
+            template_dirs: matches.values_of("template-dir").map(|dirs| dirs.map(PathBuf::from).collect()).unwrap_or_default(),
+        };
+        if let Err(e) = generate::generate_day(day, year, &examples, options) {
+            eprintln!("Generate failure: {:?}", e);
+            process::exit(1);
         }
         return;
     }
-    let challenge = matches.value_of("challenge").unwrap();
+
+    set_panic_hook();
+
+    let part_selector = cli::parts(&matches).unwrap();
     let allow_remote = matches.is_present("remote");
-    let session = matches.value_of("session").unwrap_or("");
-    let raw_input = load_input::load(day, year, session, allow_remote, None).await.unwrap();
-
-    let mut solution_handler = SolutionHandler::new();
-    let handlers = vec![
-        Day1Handler::new(),
-        Day2Handler::new(),
-        Day3Handler::new(),
-        Day4Handler::new(),
-        Day5Handler::new(),
-        Day6Handler::new(),
-    ];
-    
-    solution_handler.register(handlers);
-
-    let (duration, res) = solution_handler.solve(day, challenge, &raw_input);
-
-    match res {
-        Ok(text) => println!("Day {} challenge {} result: {}", day, challenge, text),
-        Err(e) => println!("Error running solution: {:?}", e),
+    let example = cli::example(&matches);
+    // samples are always checked against their expected answers
+    let check = matches.is_present("check") || example.is_some();
+    let record = matches.is_present("record");
+    let submit = matches.is_present("submit");
+    let format = cli::format(&matches);
+
+    let solution_handler = registry::build();
+    let days = solution_handler.days(year, &cli::days(&matches));
+    if days.is_empty() {
+        eprintln!("No solutions registered for year {} day {}", year, day);
+        process::exit(1);
+    }
+
+    let client_opt = if allow_remote || submit {
+        match AocClient::new(base_url) {
+            Ok(client) if matches.is_present("no-cache") => Some(client.with_cache_dir(None)),
+            Ok(client) => Some(client),
+            Err(e) => {
+                eprintln!("WARN: Client setup failure: {:?}", e);
+                None
+            },
+        }
+    } else {
+        None
     };
 
-    let display_time = if duration.num_milliseconds() > 0 {
-        duration.num_milliseconds() as f64
+    // inputs live for the whole run, leaked as the registered handlers borrow their input for as long as they live
+    let leak = |raw_input: String| -> &'static str { Box::leak(raw_input.into_boxed_str()) };
+    let mut inputs = vec![];
+    if let Some(input_arg) = matches.value_of("input") {
+        let named_inputs = match load_input::load_override(input_arg) {
+            Ok(named_inputs) => named_inputs,
+            Err(e) => {
+                eprintln!("Input load failure: {:?}", e);
+                process::exit(1);
+            },
+        };
+        let named_inputs: Vec<(String, &'static str)> = named_inputs.into_iter().map(|(name, raw_input)| (name, leak(raw_input))).collect();
+        for day in &days {
+            for (name, raw_input) in &named_inputs {
+                inputs.push(DayInput { day: day.clone(), name: Some(name.clone()), text: Ok(*raw_input) });
+            }
+        }
+    } else if let Some(example_num) = example {
+        for day in &days {
+            let input = load_input::load_example(day, year, example_num, None).await;
+            inputs.push(DayInput { day: day.clone(), name: Some(load_input::example_name(example_num)), text: input.map(leak).map_err(|e| vec![e]) });
+        }
     } else {
-        duration.num_microseconds().unwrap() as f64 / 1000.
+        for day in &days {
+            let input = load_input::load(day, year, client_opt.as_ref().filter(|_| allow_remote), None).await;
+            inputs.push(DayInput { day: day.clone(), name: None, text: input.map(leak) });
+        }
+    }
+
+    let day_parts = |day: &str| match part_selector {
+        PartSelector::All => solution_handler.parts(year, day),
+        PartSelector::Only(part) => vec![part],
     };
-    println!("Took {} ms to solve", display_time);
+
+    if let Some((runs, warmup)) = cli::bench(&matches) {
+        let mut bench_reports = vec![];
+        for input in &inputs {
+            for part in day_parts(&input.day) {
+                match input.text {
+                    Ok(raw_input) => {
+                        let mut bench_report = bench::run(&solution_handler, year, &input.day, part, raw_input, warmup, runs);
+                        bench_report.input = input.name.clone();
+                        bench_reports.push(bench_report);
+                    },
+                    Err(ref e) => eprintln!("WARN: Skipping day {} benchmark: {:?}", input.day, e),
+                }
+            }
+        }
+        bench::print(&bench_reports, format);
+
+        let commit = history::current_commit();
+        let entries: Vec<HistoryEntry> = bench_reports.iter()
+            .filter(|report| report.result.is_ok() && report.input.is_none())
+            .filter_map(|report| report.solve.map(|solve| HistoryEntry::new(
+                &commit, &report.year, &report.day, report.part, solve.runs, report.parse.map(|parse| parse.median), solve.median)))
+            .collect();
+        let history_path = Path::new(matches.value_of("history").unwrap());
+        let mut regressed = false;
+        if let Some(threshold) = cli::compare(&matches) {
+            match history::load(history_path) {
+                Ok(history) => {
+                    let (table, is_regressed) = history::comparison(&history, &entries, threshold);
+                    regressed = is_regressed;
+                    // structured output keeps stdout to the bench report
+                    if format == OutputFormat::Text {
+                        print!("\n{}", table);
+                    } else {
+                        eprint!("{}", table);
+                    }
+                },
+                // without a baseline a regression would go unnoticed, and this run would become the next one
+                Err(e) => {
+                    eprintln!("History load failure, timings not compared or added: {:?}", e);
+                    process::exit(1);
+                },
+            }
+        }
+        // a regressed run would become the next baseline
+        if regressed {
+            eprintln!("Regression found, timings not added to {}", history_path.display());
+            process::exit(1);
+        }
+        if let Err(e) = history::append(history_path, &entries) {
+            eprintln!("WARN: History save failure: {:?}", e);
+        }
+        return;
+    }
+
+    // one job per day input, so a day that parses separately parses it once for every part
+    let jobs: Vec<(String, Vec<Part>, &str)> = inputs.iter()
+        .filter_map(|input| input.text.as_ref().ok().map(|raw_input| (input.day.clone(), day_parts(&input.day), *raw_input)))
+        .collect();
+    let mut solutions = solution_handler.solve_jobs(year, &jobs, cli::jobs(&matches), &cli::limits(&matches)).into_iter();
+
+    let mut reports = vec![];
+    for day in &days {
+        let answers_path = match example {
+            Some(example_num) => answers::example_path(&load_input::day_path(day, year, None).join(load_input::example_name(example_num))),
+            None => answers::path(day, year, None),
+        };
+        let mut answers_opt = if check || record {
+            match Answers::load(&answers_path) {
+                Ok(answers) => Some(answers),
+                Err(e) => {
+                    eprintln!("WARN: Answers load failure: {:?}", e);
+                    None
+                },
+            }
+        } else {
+            None
+        };
+        let mut recorded = false;
+
+        for input in inputs.iter().filter(|input| &input.day == day) {
+            for part in day_parts(day) {
+                let (timing, result) = match &input.text {
+                    Ok(_) => solutions.next().unwrap(),
+                    Err(e) => (Timing::default(), Err(SolveError::new(ErrorKind::Load, format!("{:?}", e)))),
+                };
+                let answer = result.as_ref().ok().map(|text| text.as_str());
+                let check_status = match &answers_opt {
+                    Some(answers) if check => Some(answers.check(part, answer)),
+                    None if check => Some(CheckStatus::Unknown),
+                    _ => None,
+                };
+                if let (Some(answers), Some(answer), true) = (answers_opt.as_mut(), answer, record) {
+                    recorded = answers.record(part, answer) || recorded;
+                }
+                reports.push(SolveReport { year: year.to_string(), day: day.clone(), input: input.name.clone(), part, timing, result, check: check_status });
+            }
+        }
+
+        if let (Some(answers), true) = (answers_opt, recorded) {
+            if let Err(e) = answers.save(&answers_path) {
+                eprintln!("WARN: Answers save failure: {:?}", e);
+            }
+        }
+    }
+
+    report::print(&reports, format);
+
+    if let (Some(client), true) = (&client_opt, submit) {
+        // structured output keeps stdout to the report
+        let notify = |line: String| if format == OutputFormat::Text { println!("{}", line) } else { eprintln!("{}", line) };
+        for report in &reports {
+            let answer = match &report.result {
+                Ok(answer) => answer,
+                Err(_) => continue,
+            };
+            let answers_path = answers::path(&report.day, year, None);
+            let mut answers = match Answers::load(&answers_path) {
+                Ok(answers) => answers,
+                Err(e) => {
+                    eprintln!("WARN: Answers load failure, not submitting day {}: {:?}", report.day, e);
+                    continue;
+                },
+            };
+            let prefix = format!("Day {} challenge {} answer {}", report.day, report.part, answer);
+            match submit::submit(client, &mut answers, year, &report.day, report.part, answer).await {
+                Ok(outcome) => {
+                    notify(format!("{}: {}", prefix, outcome));
+                    if let Err(e) = answers.save(&answers_path) {
+                        eprintln!("WARN: Answers save failure: {:?}", e);
+                    }
+                    if let SubmitOutcome::RateLimited(_) = outcome {
+                        break;
+                    }
+                },
+                Err(SubmitError::AlreadyCorrect) => notify(format!("{}: already accepted, not submitted", prefix)),
+                Err(SubmitError::UnknownPart(part)) => notify(format!("{}: not submitted, challenge {} has no answer form", prefix, part)),
+                Err(SubmitError::MultiLine) => notify(format!("{}: not submitted, multi-line answers have to be read and entered by hand", prefix)),
+                Err(SubmitError::KnownWrong(reason)) => notify(format!("{}: not submitted, {}", prefix, reason)),
+                Err(SubmitError::Load(e)) => eprintln!("{}: submit failure: {:?}", prefix, e),
+            }
+        }
+    }
+
+    let failed = reports.iter().any(|report| matches!(report.check, Some(CheckStatus::Fail(_))));
+    if failed {
+        process::exit(1);
+    }
 }
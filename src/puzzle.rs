@@ -0,0 +1,307 @@
+use std::fs;
+
+use chrono::Utc;
+
+use crate::client::{ensure_unlocked, AocClient};
+use crate::generate::Example;
+use crate::load_input::{day_path, write_atomic, LoadError};
+
+/// A day's puzzle description, `parts` is 2 once part 2 is unlocked
+#[derive(Debug, PartialEq)]
+pub struct Puzzle {
+    pub markdown: String,
+    /// Per part, the `<pre><code>` blocks of its article in page order
+    pub examples: Vec<Vec<String>>,
+    /// Per part, the last highlighted `<code><em>` value, which is usually the example's answer. `None` when the part highlights none
+    pub answers: Vec<Option<String>>,
+    pub parts: usize,
+}
+
+enum Token<'a> {
+    Text(&'a str),
+    Open(String, Option<String>),
+    Close(String),
+}
+
+impl Puzzle {
+    /// Converts the page's `<article>`s, one per unlocked part
+    pub fn parse(html: &str, base_url: &str) -> Option<Puzzle> {
+        let mut articles = vec![];
+        let mut rest = html;
+        while let Some(start) = rest.find("<article") {
+            let article = &rest[start..];
+            let end = article.find("</article>").map(|end| end + "</article>".len()).unwrap_or(article.len());
+            articles.push(&article[..end]);
+            rest = &article[end..];
+        }
+        if articles.is_empty() {
+            return None;
+        }
+
+        let mut markdown = String::new();
+        let mut examples = vec![];
+        let mut answers = vec![];
+        for article in &articles {
+            let (article_markdown, article_examples, answer) = convert(article, base_url);
+            markdown.push_str(&article_markdown);
+            markdown.push('\n');
+            examples.push(article_examples);
+            answers.push(answer);
+        }
+
+        Some(Puzzle { markdown: markdown.trim_end().to_string() + "\n", examples, answers, parts: articles.len() })
+    }
+
+    /// Each part's first example block with the answer highlighted in the same article,
+    /// a part without an example block of its own expects its answer from the previous example
+    pub fn paired_examples(&self) -> Vec<Example> {
+        let mut examples: Vec<Example> = vec![];
+        for (part_idx, (blocks, answer)) in self.examples.iter().zip(&self.answers).enumerate() {
+            match (blocks.first(), examples.last_mut()) {
+                (None, Some(example)) => {
+                    example.answers.resize(part_idx, None);
+                    example.answers.push(answer.clone());
+                },
+                (block, _) => {
+                    let mut answers = vec![None; part_idx];
+                    answers.push(answer.clone());
+                    examples.push(Example { input: block.cloned(), answers });
+                },
+            }
+        }
+
+        examples
+    }
+}
+
+/// Downloads the puzzle page and saves it as `README.md` next to the day's input
+pub async fn fetch(client: &AocClient, day_num: &str, year: &str, base_path_opt: Option<&str>) -> Result<Puzzle, LoadError> {
+    ensure_unlocked(year, day_num, Utc::now())?;
+    // always requested, the page gains part 2 once part 1 is solved
+    let html = client.refresh(&format!("/{}/day/{}", year, day_num), check_puzzle).await?;
+    let puzzle = Puzzle::parse(&html, &client.url("")).ok_or_else(|| LoadError::HttpError { status: None, reason: String::from("no puzzle description") })?;
+
+    let dir_path = day_path(day_num, year, base_path_opt);
+    fs::create_dir_all(&dir_path).map_err(|e| LoadError::WriteError(format!("{} - {}", e, dir_path.display())))?;
+    write_atomic(&dir_path.join("README.md"), &puzzle.markdown)?;
+
+    Ok(puzzle)
+}
+
+fn check_puzzle(text: &str) -> Result<(), String> {
+    if text.contains("<article") {
+        Ok(())
+    } else {
+        Err(text.trim().lines().next().unwrap_or("empty response").trim().to_string())
+    }
+}
+
+fn tokens(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        if start > 0 {
+            tokens.push(Token::Text(&rest[..start]));
+        }
+        let end = match rest[start..].find('>') {
+            Some(end) => start + end,
+            None => break,
+        };
+        let tag = rest[start + 1..end].trim_end_matches('/');
+        let name: String = tag.trim_start_matches('/').chars().take_while(|chr| chr.is_ascii_alphanumeric()).collect::<String>().to_lowercase();
+        if tag.starts_with('/') {
+            tokens.push(Token::Close(name));
+        } else {
+            let href = tag.split_once("href=\"").and_then(|(_, href)| href.split_once('"')).map(|(href, _)| decode(href));
+            tokens.push(Token::Open(name, href));
+        }
+        rest = &rest[end + 1..];
+    }
+    if !rest.is_empty() {
+        tokens.push(Token::Text(rest));
+    }
+
+    tokens
+}
+
+/// Markdown for one article, the examples in it and its last highlighted code
+fn convert(article: &str, base_url: &str) -> (String, Vec<String>, Option<String>) {
+    let mut markdown = String::new();
+    let mut examples = vec![];
+    let mut example: Option<String> = None;
+    let mut in_code = false;
+    let mut highlight: Option<String> = None;
+    let mut answer = None;
+    let mut links = vec![];
+
+    for token in tokens(article) {
+        match token {
+            Token::Text(text) => match (example.as_mut(), highlight.as_mut()) {
+                (Some(example), _) => example.push_str(&decode(text)),
+                (None, Some(highlight)) => {
+                    highlight.push_str(&decode(text));
+                    markdown.push_str(&decode(text));
+                },
+                (None, None) => {
+                    // html whitespace runs collapse to a single space
+                    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                    if text.starts_with(char::is_whitespace) && !markdown.is_empty() && !markdown.ends_with(char::is_whitespace) {
+                        markdown.push(' ');
+                    }
+                    markdown.push_str(&decode(&collapsed));
+                    if !collapsed.is_empty() && text.ends_with(char::is_whitespace) {
+                        markdown.push(' ');
+                    }
+                },
+            },
+            Token::Open(name, href) => match name.as_str() {
+                "pre" => example = Some(String::new()),
+                "code" if example.is_none() => {
+                    in_code = true;
+                    markdown.push('`');
+                },
+                "em" if example.is_none() && in_code => highlight = Some(String::new()),
+                "em" if example.is_none() => markdown.push_str("**"),
+                "h2" => markdown.push_str("## "),
+                "li" => markdown.push_str("- "),
+                "a" => {
+                    let href = href.map(|href| if href.starts_with('/') { format!("{}{}", base_url, href) } else { href });
+                    links.push(href);
+                    markdown.push('[');
+                },
+                "br" => markdown.push('\n'),
+                _ => {},
+            },
+            Token::Close(name) => match name.as_str() {
+                "pre" => if let Some(example) = example.take() {
+                    markdown.push_str(&format!("```\n{}\n```\n\n", example.trim_end_matches('\n')));
+                    examples.push(example.trim_end_matches('\n').to_string());
+                },
+                "code" if example.is_none() => {
+                    in_code = false;
+                    markdown.push('`');
+                },
+                "em" if example.is_none() && in_code => answer = highlight.take().or(answer),
+                "em" if example.is_none() => markdown.push_str("**"),
+                "h2" | "p" => markdown.push_str("\n\n"),
+                "li" | "ul" => markdown.push('\n'),
+                "a" => match links.pop().flatten() {
+                    Some(href) => markdown.push_str(&format!("]({})", href)),
+                    None => markdown.push(']'),
+                },
+                _ => {},
+            },
+        }
+    }
+
+    (markdown.lines().map(str::trim_end).collect::<Vec<_>>().join("\n").trim().to_string() + "\n", examples, answer)
+}
+
+fn decode(text: &str) -> String {
+    let mut decoded = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest.find(';').map(|end| &rest[1..end]);
+        let chr = entity.and_then(|entity| match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => entity.strip_prefix("#x").map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(|dec| dec.parse::<u32>()))
+                .and_then(|code| code.ok())
+                .and_then(char::from_u32),
+        });
+        match (chr, entity) {
+            (Some(chr), Some(entity)) => {
+                decoded.push(chr);
+                rest = &rest[entity.len() + 2..];
+            },
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            },
+        }
+    }
+    decoded.push_str(rest);
+
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, time::Duration};
+
+    use crate::client::{tests::serve_once, AocClient};
+    use super::{fetch, Puzzle};
+
+    const PAGE: &str = "<html><body><main>\n\
+        <article class=\"day-desc\"><h2>--- Day 1: Sonar Sweep ---</h2><p>For example, suppose you had the following <a href=\"/2021/day/1/input\">report</a>:</p>\n\
+        <pre><code>199\n200\n&lt;208&gt;\n</code></pre>\n\
+        <p>This report has <code><em>7</em></code> measurements that are <em>larger</em> than the previous one.</p>\n\
+        <ul>\n<li>One</li>\n<li>Two &amp; three</li>\n</ul>\n\
+        </article>\n<p>Your puzzle answer was <code>1564</code>.</p>\n\
+        <article class=\"day-desc\"><h2 id=\"part2\">--- Part Two ---</h2><p>Consider sums:</p><pre><code>A: 607\nB: 618\n</code></pre><p>There are <code><em>5</em></code> increases.</p></article>\n\
+        </main></body></html>";
+
+    #[test]
+    fn parse_articles() {
+        let puzzle = Puzzle::parse(PAGE, "https://adventofcode.com").unwrap();
+        assert_eq!(puzzle.parts, 2);
+        assert_eq!(puzzle.examples, vec![vec![String::from("199\n200\n<208>")], vec![String::from("A: 607\nB: 618")]]);
+        assert_eq!(puzzle.answers, vec![Some(String::from("7")), Some(String::from("5"))]);
+        assert_eq!(puzzle.markdown, "## --- Day 1: Sonar Sweep ---\n\n\
+            For example, suppose you had the following [report](https://adventofcode.com/2021/day/1/input):\n\n\
+            ```\n199\n200\n<208>\n```\n\n\
+            This report has `7` measurements that are **larger** than the previous one.\n\n\
+            - One\n- Two & three\n\n\
+            ## --- Part Two ---\n\n\
+            Consider sums:\n\n\
+            ```\nA: 607\nB: 618\n```\n\n\
+            There are `5` increases.\n");
+        assert_eq!(Puzzle::parse("<html>Please log in</html>", ""), None);
+    }
+
+    #[test]
+    fn answers_stay_with_their_part() {
+        let page = "<article><p>No highlighted answer, only <em>emphasis</em>.</p></article>\n\
+            <article><p>There are <code><em>5</em></code> increases.</p></article>";
+        let puzzle = Puzzle::parse(page, "").unwrap();
+        assert_eq!(puzzle.answers, vec![None, Some(String::from("5"))]);
+    }
+
+    #[test]
+    fn examples_pair_with_their_answers() {
+        let examples = Puzzle::parse(PAGE, "").unwrap().paired_examples();
+        assert_eq!(examples.len(), 2);
+        assert_eq!(examples[0].input.as_deref(), Some("199\n200\n<208>"));
+        assert_eq!(examples[0].answers, vec![Some(String::from("7"))]);
+        assert_eq!(examples[1].input.as_deref(), Some("A: 607\nB: 618"));
+        assert_eq!(examples[1].answers, vec![None, Some(String::from("5"))]);
+
+        // part 2 reusing the part 1 example
+        let page = "<article><pre><code>1\n2\n</code></pre><p><code><em>3</em></code></p></article>\n\
+            <article><p>Now it is <code><em>2</em></code>.</p></article>";
+        let examples = Puzzle::parse(page, "").unwrap().paired_examples();
+        assert_eq!(examples.len(), 1);
+        assert_eq!(examples[0].answers, vec![Some(String::from("3")), Some(String::from("2"))]);
+    }
+
+    #[tokio::test]
+    async fn fetch_saves_readme() {
+        let base_path = env::temp_dir().join(format!("advent-puzzle-{}", std::process::id()));
+        let (base_url, server) = serve_once("200 OK", PAGE);
+        let client = AocClient::new(&base_url).unwrap().with_cache_dir(None).with_min_delay(Duration::ZERO);
+
+        let puzzle = fetch(&client, "1", "2021", base_path.to_str()).await.unwrap();
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /2021/day/1 HTTP/1.1"), "{}", request);
+        assert_eq!(fs::read_to_string(base_path.join("year2021").join("day1").join("README.md")).unwrap(), puzzle.markdown);
+
+        fs::remove_dir_all(&base_path).unwrap();
+    }
+}
@@ -0,0 +1,90 @@
+use crate::handler::SolutionHandler;
+use crate::{year2021, year2022, year2023};
+
+pub const DEFAULT_YEAR: &str = "2023";
+
+pub fn build<'a>() -> SolutionHandler<'a, &'a str> {
+    let mut solution_handler = SolutionHandler::new();
+    solution_handler.register("2021", year2021::handlers());
+    solution_handler.register("2022", year2022::handlers());
+    solution_handler.register("2023", year2023::handlers());
+
+    solution_handler
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io::{self, Write}};
+
+    use crate::answers::{self, Answers, CheckStatus};
+    use crate::handler::Part;
+    use crate::load_input;
+    use super::build;
+
+    /// One test per challenge with a committed answer, listed in `GOLDEN` so coverage can be checked
+    macro_rules! golden_answers {
+        ($($name:ident => ($year:literal, $day:literal, $part:literal),)*) => {
+            const GOLDEN: &[(&str, &str, &str)] = &[$(($year, $day, $part)),*];
+            $(
+                #[test]
+                fn $name() {
+                    check_golden($year, $day, $part);
+                }
+            )*
+        };
+    }
+
+    // generated by build.rs from the `answers` files
+    include!(concat!(env!("OUT_DIR"), "/golden_answers.rs"));
+
+    /// Written straight to stderr so the notice shows without `--nocapture`
+    fn skip_notice(notice: &str) {
+        let _ = io::stderr().write_all(format!("golden answers: skipped {}\n", notice).as_bytes());
+    }
+
+    /// Solves the day's `input` and compares it with the answer recorded in its `answers` file,
+    /// skipped with a notice when either is missing
+    fn check_golden(year: &str, day: &str, part: &str) {
+        let part: Part = part.parse().unwrap();
+        let input = match fs::read_to_string(load_input::day_path(day, year, None).join("input")) {
+            Ok(input) => input,
+            Err(_) => return skip_notice(&format!("{} day {} challenge {}: no input", year, day, part)),
+        };
+        let answers = Answers::load(&answers::path(day, year, None)).unwrap();
+        if answers.get(part).is_none() {
+            return skip_notice(&format!("{} day {} challenge {}: no recorded answer", year, day, part));
+        }
+
+        let solution_handler = build();
+        let (_, result) = solution_handler.solve(year, day, part, &input);
+        match answers.check(part, result.as_ref().ok().map(String::as_str)) {
+            CheckStatus::Pass => {},
+            status => panic!("{} day {} challenge {}: {}, got {:?}", year, day, part, status, result),
+        }
+    }
+
+    /// Lists every registered challenge without an input or answer, and checks each recorded answer has a golden test
+    #[test]
+    fn golden_answers_are_tested() {
+        let solution_handler = build();
+        let mut untested = vec![];
+        for year in solution_handler.years() {
+            for day in solution_handler.days(&year, &(1..=25)) {
+                let has_input = load_input::day_path(&day, &year, None).join("input").is_file();
+                let answers = Answers::load(&answers::path(&day, &year, None)).unwrap();
+                for part in solution_handler.parts(&year, &day) {
+                    if !has_input {
+                        skip_notice(&format!("{} day {} challenge {}: no input", year, day, part));
+                    } else if answers.get(part).is_none() {
+                        skip_notice(&format!("{} day {} challenge {}: no recorded answer", year, day, part));
+                    } else if !GOLDEN.contains(&(year.as_str(), day.as_str(), part.to_string().as_str())) {
+                        untested.push(format!("{} day {} challenge {}", year, day, part));
+                    }
+                }
+            }
+        }
+
+        assert!(!GOLDEN.is_empty(), "no golden answers are checked");
+        assert!(untested.is_empty(), "recorded answers without a golden test, rebuild to regenerate them:\n{}", untested.join("\n"));
+    }
+}
@@ -0,0 +1,288 @@
+use std::{str::FromStr, time::Duration};
+
+use serde_json::{json, Value};
+
+use crate::answers::CheckStatus;
+use crate::handler::{ErrorKind, Part, SolveError, Timing};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Csv,
+    Tap,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            "tap" => Ok(OutputFormat::Tap),
+            _ => Err(format!("invalid format '{}', expected text, json, csv or tap", value)),
+        }
+    }
+}
+
+pub struct SolveReport {
+    pub year: String,
+    pub day: String,
+    /// Name of an input given on the command line, `None` for the day's own input
+    pub input: Option<String>,
+    pub part: Part,
+    pub timing: Timing,
+    pub result: Result<String, SolveError>,
+    pub check: Option<CheckStatus>,
+}
+
+pub fn display_ms(duration: &Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64() * 1000.)
+}
+
+pub fn print(reports: &[SolveReport], format: OutputFormat) {
+    match (format, reports) {
+        (OutputFormat::Text, [report]) => print_single(report),
+        (OutputFormat::Text, _) => print_summary(reports),
+        (OutputFormat::Json, _) => println!("{}", to_json(reports)),
+        (OutputFormat::Csv, _) => print!("{}", to_csv(reports)),
+        (OutputFormat::Tap, _) => print!("{}", to_tap(reports)),
+    }
+}
+
+pub fn duration_ms(duration: &Duration) -> f64 {
+    duration.as_secs_f64() * 1000.
+}
+
+fn failed(report: &SolveReport) -> bool {
+    report.result.is_err() || matches!(report.check, Some(CheckStatus::Fail(_)))
+}
+
+pub fn to_json(reports: &[SolveReport]) -> Value {
+    Value::Array(reports.iter().map(|report| json!({
+        "year": report.year,
+        "day": report.day,
+        "input": report.input,
+        "part": report.part.to_string(),
+        "answer": report.result.as_ref().ok(),
+        "duration_ms": duration_ms(&report.timing.total()),
+        "parse_ms": report.timing.parse.as_ref().map(duration_ms),
+        "solve_ms": duration_ms(&report.timing.solve),
+        "error": report.result.as_ref().err().map(|e| json!({
+            "kind": e.kind.to_string(),
+            "message": e.message,
+            "line": e.location.map(|location| location.line),
+            "column": e.location.and_then(|location| location.column),
+        })),
+        "check": report.check.as_ref().map(|check| check.to_string()),
+    })).collect())
+}
+
+/// Quotes a field when it holds a separator, quote or line break
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn to_csv(reports: &[SolveReport]) -> String {
+    let mut text = String::from("year,day,input,part,answer,duration_ms,error_kind,error,check\n");
+    for report in reports {
+        let fields = [
+            report.year.clone(),
+            report.day.clone(),
+            report.input.clone().unwrap_or_default(),
+            report.part.to_string(),
+            report.result.as_ref().map(|answer| answer.clone()).unwrap_or_default(),
+            display_ms(&report.timing.total()),
+            report.result.as_ref().err().map(|e| e.kind.to_string()).unwrap_or_default(),
+            report.result.as_ref().err().map(|e| e.to_string()).unwrap_or_default(),
+            report.check.as_ref().map(|check| check.to_string()).unwrap_or_default(),
+        ];
+        text.push_str(&fields.iter().map(|field| csv_field(field)).collect::<Vec<String>>().join(","));
+        text.push('\n');
+    }
+
+    text
+}
+
+/// Test Anything Protocol, errors and failed checks are `not ok` with a YAML diagnostic block
+pub fn to_tap(reports: &[SolveReport]) -> String {
+    let mut text = format!("TAP version 13\n1..{}\n", reports.len());
+    for (idx, report) in reports.iter().enumerate() {
+        let status = if failed(report) { "not ok" } else { "ok" };
+        let directive = match &report.result {
+            Err(e) if e.kind == ErrorKind::Unimplemented => " # TODO not implemented",
+            _ => "",
+        };
+        let input = report.input.as_ref().map(|name| format!(" input {}", name)).unwrap_or_default();
+        text.push_str(&format!("{} {} - {} day {}{} part {}{}\n", status, idx + 1, report.year, report.day, input, report.part, directive));
+        text.push_str("  ---\n");
+        match &report.result {
+            Ok(answer) => text.push_str(&format!("  answer: {}\n", json!(answer))),
+            Err(e) => {
+                text.push_str(&format!("  kind: {}\n", e.kind));
+                text.push_str(&format!("  message: {}\n", json!(e.to_string())));
+            },
+        }
+        if let Some(check) = &report.check {
+            text.push_str(&format!("  check: {}\n", json!(check.to_string())));
+        }
+        text.push_str(&format!("  duration_ms: {}\n", display_ms(&report.timing.total())));
+        text.push_str("  ...\n");
+    }
+
+    text
+}
+
+pub fn print_single(report: &SolveReport) {
+    match &report.result {
+        Ok(text) => match &report.input {
+            Some(name) => println!("Day {} challenge {} result for {}: {}", report.day, report.part, name, text),
+            None => println!("Day {} challenge {} result: {}", report.day, report.part, text),
+        },
+        Err(e) => println!("Error running solution: {}", e),
+    };
+    if let Some(check) = &report.check {
+        println!("Check: {}", check);
+    }
+    if let Some(parse) = &report.timing.parse {
+        println!("Took {} ms to parse", display_ms(parse));
+    }
+    println!("Took {} ms to solve", display_ms(&report.timing.solve));
+}
+
+pub fn print_summary(reports: &[SolveReport]) {
+    print!("{}", to_summary(reports));
+}
+
+/// Table of every report, multi-line answers are listed below it so the table keeps one line per row
+pub fn to_summary(reports: &[SolveReport]) -> String {
+    let with_check = reports.iter().any(|report| report.check.is_some());
+    let with_input = reports.iter().any(|report| report.input.is_some());
+    let mut header = vec!["Year", "Day"];
+    if with_input {
+        header.push("Input");
+    }
+    header.extend(["Part", "Answer", "Time (ms)"]);
+    if with_check {
+        header.push("Check");
+    }
+    let mut below = vec![];
+    let rows: Vec<Vec<String>> = reports.iter().map(|report| {
+        let mut row = vec![report.year.clone(), report.day.clone()];
+        if with_input {
+            row.push(report.input.clone().unwrap_or_default());
+        }
+        let input = report.input.as_ref().map(|input| format!(" input {}", input)).unwrap_or_default();
+        let title = format!("{} day {}{} part {}", report.year, report.day, input, report.part);
+        let answer = match &report.result {
+            Ok(text) if text.contains('\n') => {
+                below.push(format!("{}:\n{}", title, text.trim_matches('\n')));
+                String::from("(below)")
+            },
+            Ok(text) => text.clone(),
+            Err(e) => e.to_string(),
+        };
+        row.extend([report.part.to_string(), answer, display_ms(&report.timing.total())]);
+        if with_check {
+            let check = match &report.check {
+                Some(CheckStatus::Fail(expected)) if expected.contains('\n') => {
+                    below.push(format!("{} expected:\n{}", title, expected.trim_matches('\n')));
+                    String::from("FAIL (expected below)")
+                },
+                check => check.as_ref().map(|check| check.to_string()).unwrap_or_default(),
+            };
+            row.push(check);
+        }
+        row
+    }).collect();
+
+    // sized by the longest line so a cell can never stretch a column past what it shows
+    let cell_width = |cell: &str| cell.lines().map(|line| line.chars().count()).max().unwrap_or_default();
+    let mut widths: Vec<usize> = header.iter().map(|title| title.len()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell_width(cell));
+        }
+    }
+
+    let format_row = |cells: Vec<&str>| cells.iter().zip(&widths)
+        .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+        .collect::<Vec<String>>()
+        .join(" | ")
+        .trim_end()
+        .to_string();
+
+    let mut summary = format!("{}\n", format_row(header.clone()));
+    summary.push_str(&format!("{}\n", widths.iter().map(|width| "-".repeat(*width)).collect::<Vec<String>>().join("-+-")));
+    for row in &rows {
+        summary.push_str(&format!("{}\n", format_row(row.iter().map(|cell| cell.as_str()).collect())));
+    }
+    for answer in below {
+        summary.push_str(&format!("\n{}\n", answer));
+    }
+
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::answers::CheckStatus;
+    use crate::handler::{ErrorKind, Part, SolveError, Timing};
+    use super::{to_csv, to_json, to_summary, to_tap, SolveReport};
+
+    fn reports() -> Vec<SolveReport> {
+        let timing = Timing { parse: None, solve: Duration::from_millis(2) };
+        vec![
+            SolveReport { year: String::from("2023"), day: String::from("1"), input: None, part: Part::One, timing, result: Ok(String::from("42")), check: None },
+            SolveReport { year: String::from("2023"), day: String::from("1"), input: Some(String::from("edge")), part: Part::Two, timing, result: Err(SolveError::new(ErrorKind::Parse, "bad, \"input\"")), check: None },
+        ]
+    }
+
+    #[test]
+    fn json_output() {
+        let json = to_json(&reports());
+        assert_eq!(json[0]["answer"], "42");
+        assert_eq!(json[0]["duration_ms"], 2.);
+        assert!(json[0]["error"].is_null());
+        assert!(json[0]["input"].is_null());
+        assert_eq!(json[1]["input"], "edge");
+        assert!(json[1]["answer"].is_null());
+        assert_eq!(json[1]["error"]["kind"], "Parse");
+    }
+
+    #[test]
+    fn csv_output() {
+        let csv = to_csv(&reports());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[1], "2023,1,,1,42,2.000,,,");
+        assert_eq!(lines[2], "2023,1,edge,2,,2.000,Parse,\"Parse: bad, \"\"input\"\"\",");
+    }
+
+    #[test]
+    fn tap_output() {
+        let tap = to_tap(&reports());
+        assert!(tap.starts_with("TAP version 13\n1..2\n"));
+        assert!(tap.contains("ok 1 - 2023 day 1 part 1\n"));
+        assert!(tap.contains("not ok 2 - 2023 day 1 input edge part 2\n  ---\n  kind: Parse\n"));
+    }
+
+    #[test]
+    fn multi_line_answers_below_summary() {
+        let mut reports = reports();
+        reports[0].result = Ok(String::from("\n#..#.###\n####.#.."));
+        reports[0].check = Some(CheckStatus::Fail(String::from("\n#..#.###\n####.##.")));
+        let summary = to_summary(&reports);
+        let lines: Vec<&str> = summary.lines().collect();
+        assert_eq!(lines[0], "Year | Day | Input | Part | Answer              | Time (ms) | Check");
+        assert_eq!(lines[2], "2023 | 1   |       | 1    | (below)             | 2.000     | FAIL (expected below)");
+        assert_eq!(&lines[4..], ["", "2023 day 1 part 1:", "#..#.###", "####.#..", "", "2023 day 1 part 1 expected:", "#..#.###", "####.##."]);
+    }
+}
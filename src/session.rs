@@ -0,0 +1,76 @@
+use std::{env, fs, path::PathBuf};
+
+use crate::load_input::LoadError;
+
+pub const SESSION_ENV: &str = "AOC_SESSION";
+/// Git ignored file in the working directory, checked last
+pub const SESSION_FILE: &str = ".session";
+
+/// `<config dir>/advent`, the config dir being `XDG_CONFIG_HOME`, `~/.config` or `APPDATA` on Windows
+pub fn config_dir() -> Option<PathBuf> {
+    let config_dir = env::var_os("XDG_CONFIG_HOME").map(PathBuf::from)
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(config_dir.join("advent"))
+}
+
+/// `<config dir>/advent/session`
+pub fn config_path() -> Option<PathBuf> {
+    config_dir().map(|config_dir| config_dir.join("session"))
+}
+
+/// Session token from the `AOC_SESSION` environment variable, the config file or `.session`, in that order
+pub fn resolve() -> Result<String, LoadError> {
+    let mut files = vec![];
+    if let Some(config_path) = config_path() {
+        files.push(config_path);
+    }
+    files.push(PathBuf::from(SESSION_FILE));
+
+    resolve_from(env::var(SESSION_ENV).ok(), &files)
+}
+
+fn resolve_from(env_value: Option<String>, files: &[PathBuf]) -> Result<String, LoadError> {
+    let token = |value: &str| Some(value.trim().to_string()).filter(|token| !token.is_empty());
+
+    if let Some(session) = env_value.as_deref().and_then(token) {
+        return Ok(session);
+    }
+    for file in files {
+        if let Some(session) = fs::read_to_string(file).ok().as_deref().and_then(token) {
+            return Ok(session);
+        }
+    }
+
+    let checked: Vec<String> = files.iter().map(|file| file.display().to_string()).collect();
+    Err(LoadError::MissingSession(format!("set {} or write the token to one of: {}", SESSION_ENV, checked.join(", "))))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use crate::load_input::LoadError;
+    use super::resolve_from;
+
+    #[test]
+    fn resolve_order() {
+        let dir = env::temp_dir().join(format!("advent-session-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let config = dir.join("session");
+        let local = dir.join(".session");
+        fs::write(&local, "local-token\n").unwrap();
+        let files = [config.clone(), local.clone()];
+
+        assert_eq!(resolve_from(Some(String::from("env-token")), &files).unwrap(), "env-token");
+        assert_eq!(resolve_from(Some(String::from(" ")), &files).unwrap(), "local-token");
+        fs::write(&config, "config-token").unwrap();
+        assert_eq!(resolve_from(None, &files).unwrap(), "config-token");
+        fs::remove_file(&config).unwrap();
+        fs::remove_file(&local).unwrap();
+        assert!(matches!(resolve_from(None, &files), Err(LoadError::MissingSession(_))));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
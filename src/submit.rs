@@ -0,0 +1,189 @@
+use std::{fmt::Display, time::Duration};
+
+use chrono::Utc;
+
+use crate::answers::Answers;
+use crate::client::{ensure_unlocked, AocClient};
+use crate::handler::Part;
+use crate::load_input::LoadError;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SubmitOutcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    AlreadySolved,
+    /// Wait time from the response, when it could be read
+    RateLimited(Option<Duration>),
+}
+
+#[derive(Debug)]
+pub enum SubmitError {
+    /// Not submitted, the answers file already rules the answer out
+    KnownWrong(String),
+    /// Not submitted, the server already accepted this answer
+    AlreadyCorrect,
+    /// Not submitted, the server only takes answers for challenges 1 and 2
+    UnknownPart(Part),
+    /// Not submitted, a multi-line answer has to be read and typed in by hand
+    MultiLine,
+    Load(LoadError),
+}
+
+impl From<LoadError> for SubmitError {
+    fn from(e: LoadError) -> Self {
+        SubmitError::Load(e)
+    }
+}
+
+impl Display for SubmitOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubmitOutcome::Correct => write!(f, "correct"),
+            SubmitOutcome::TooHigh => write!(f, "too high"),
+            SubmitOutcome::TooLow => write!(f, "too low"),
+            SubmitOutcome::Wrong => write!(f, "wrong"),
+            SubmitOutcome::AlreadySolved => write!(f, "already solved"),
+            SubmitOutcome::RateLimited(Some(wait)) => write!(f, "rate limited, wait {}s", wait.as_secs()),
+            SubmitOutcome::RateLimited(None) => write!(f, "rate limited"),
+        }
+    }
+}
+
+impl SubmitOutcome {
+    /// Reads the outcome from the answer page's `<article>` text
+    pub fn parse(html: &str) -> Option<SubmitOutcome> {
+        let article = html.split_once("<article>").map(|(_, article)| article).unwrap_or(html);
+        let article = article.split_once("</article>").map(|(article, _)| article).unwrap_or(article);
+
+        if article.contains("That's the right answer") {
+            Some(SubmitOutcome::Correct)
+        } else if article.contains("You gave an answer too recently") {
+            Some(SubmitOutcome::RateLimited(parse_wait(article)))
+        } else if article.contains("You don't seem to be solving the right level") {
+            Some(SubmitOutcome::AlreadySolved)
+        } else if article.contains("That's not the right answer") {
+            if article.contains("your answer is too high") {
+                Some(SubmitOutcome::TooHigh)
+            } else if article.contains("your answer is too low") {
+                Some(SubmitOutcome::TooLow)
+            } else {
+                Some(SubmitOutcome::Wrong)
+            }
+        } else {
+            None
+        }
+    }
+}
+
+/// `You have 4m 30s left to wait` or `You have 37s left to wait`
+fn parse_wait(article: &str) -> Option<Duration> {
+    let (_, rest) = article.split_once("You have ")?;
+    let (wait, _) = rest.split_once(" left to wait")?;
+    wait.split_whitespace().try_fold(0, |secs, unit| {
+        let (value, multiplier) = match unit.strip_suffix('m') {
+            Some(minutes) => (minutes, 60),
+            None => (unit.strip_suffix('s')?, 1),
+        };
+        Some(secs + value.parse::<u64>().ok()? * multiplier)
+    }).map(Duration::from_secs)
+}
+
+fn check_outcome(text: &str) -> Result<(), String> {
+    match SubmitOutcome::parse(text) {
+        Some(_) => Ok(()),
+        None => Err(String::from("unrecognized answer response")),
+    }
+}
+
+/// Posts `answer` unless `answers` rules it out, recording correct answers and rejections in `answers`
+pub async fn submit(client: &AocClient, answers: &mut Answers, year: &str, day: &str, part: Part, answer: &str) -> Result<SubmitOutcome, SubmitError> {
+    if let Part::Bonus(_) = part {
+        return Err(SubmitError::UnknownPart(part));
+    }
+    if answer.contains('\n') {
+        return Err(SubmitError::MultiLine);
+    }
+    if answers.is_confirmed(part) && answers.get(part) == Some(answer) {
+        return Err(SubmitError::AlreadyCorrect);
+    }
+    if let Some(reason) = answers.known_wrong(part, answer) {
+        return Err(SubmitError::KnownWrong(reason));
+    }
+    ensure_unlocked(year, day, Utc::now())?;
+
+    let level = part.to_string();
+    let form = [("level", level.as_str()), ("answer", answer)];
+    let text = client.post(&format!("/{}/day/{}/answer", year, day), &form, check_outcome).await?;
+    let outcome = SubmitOutcome::parse(&text).ok_or_else(|| LoadError::HttpError { status: None, reason: String::from("unrecognized answer response") })?;
+    match outcome {
+        SubmitOutcome::Correct => answers.confirm(part, answer),
+        SubmitOutcome::TooHigh | SubmitOutcome::TooLow | SubmitOutcome::Wrong => answers.reject(part, answer, &outcome.to_string()),
+        SubmitOutcome::AlreadySolved | SubmitOutcome::RateLimited(_) => {},
+    }
+
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::answers::Answers;
+    use crate::client::{tests::serve_once, AocClient};
+    use crate::handler::Part;
+    use super::{submit, SubmitError, SubmitOutcome};
+
+    fn page(article: &str) -> String {
+        format!("<html><body><main><article><p>{}</p></article></main></body></html>", article)
+    }
+
+    #[test]
+    fn parse_outcomes() {
+        let parse = |article: &str| SubmitOutcome::parse(&page(article));
+        assert_eq!(parse("That's the right answer!  You are one gold star closer."), Some(SubmitOutcome::Correct));
+        assert_eq!(parse("That's not the right answer; your answer is too high.  If you're stuck"), Some(SubmitOutcome::TooHigh));
+        assert_eq!(parse("That's not the right answer; your answer is too low."), Some(SubmitOutcome::TooLow));
+        assert_eq!(parse("That's not the right answer.  If you're stuck"), Some(SubmitOutcome::Wrong));
+        assert_eq!(parse("You don't seem to be solving the right level.  Did you already complete it?"), Some(SubmitOutcome::AlreadySolved));
+        assert_eq!(parse("You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 4m 30s left to wait."),
+            Some(SubmitOutcome::RateLimited(Some(Duration::from_secs(270)))));
+        assert_eq!(parse("You gave an answer too recently. You have 37s left to wait."), Some(SubmitOutcome::RateLimited(Some(Duration::from_secs(37)))));
+        assert_eq!(parse("Welcome"), None);
+    }
+
+    #[tokio::test]
+    async fn rejected_answers_are_not_resubmitted() {
+        let body: &'static str = Box::leak(page("That's not the right answer; your answer is too low.").into_boxed_str());
+        let (base_url, server) = serve_once("200 OK", body);
+        let client = AocClient::new(&base_url).unwrap().with_cache_dir(None).with_min_delay(Duration::ZERO);
+        let mut answers = Answers::default();
+
+        let outcome = submit(&client, &mut answers, "2021", "1", Part::Two, "1500").await.unwrap();
+        let request = server.join().unwrap();
+        assert_eq!(outcome, SubmitOutcome::TooLow);
+        assert!(request.starts_with("POST /2021/day/1/answer HTTP/1.1"), "{}", request);
+        assert!(request.ends_with("level=2&answer=1500"), "{}", request);
+        // no server is listening anymore, so these must be refused locally
+        assert!(matches!(submit(&client, &mut answers, "2021", "1", Part::Two, "1500").await, Err(SubmitError::KnownWrong(_))));
+        assert!(matches!(submit(&client, &mut answers, "2021", "1", Part::Two, "1400").await, Err(SubmitError::KnownWrong(_))));
+        assert!(matches!(submit(&client, &mut answers, "2021", "1", Part::Bonus(3), "1").await, Err(SubmitError::UnknownPart(Part::Bonus(3)))));
+        assert!(matches!(submit(&client, &mut answers, "2021", "1", Part::Two, "\n#..#\n").await, Err(SubmitError::MultiLine)));
+    }
+
+    #[tokio::test]
+    async fn recorded_answers_are_submitted() {
+        let body: &'static str = Box::leak(page("That's the right answer!  You are one gold star closer.").into_boxed_str());
+        let (base_url, server) = serve_once("200 OK", body);
+        let client = AocClient::new(&base_url).unwrap().with_cache_dir(None).with_min_delay(Duration::ZERO);
+        let mut answers = Answers::parse("1: 1564\n").unwrap();
+
+        let outcome = submit(&client, &mut answers, "2021", "1", Part::One, "1564").await.unwrap();
+        server.join().unwrap();
+        assert_eq!(outcome, SubmitOutcome::Correct);
+        assert!(answers.is_confirmed(Part::One));
+        assert!(matches!(submit(&client, &mut answers, "2021", "1", Part::One, "1564").await, Err(SubmitError::AlreadyCorrect)));
+        assert!(matches!(submit(&client, &mut answers, "2021", "1", Part::One, "1565").await, Err(SubmitError::KnownWrong(_))));
+    }
+}
@@ -0,0 +1,176 @@
+use std::{env, io::{self, Read, Write}, path::PathBuf, process::{self, Command, Stdio}, thread};
+use std::sync::{Arc, atomic::{AtomicIsize, Ordering}, mpsc::{self, RecvTimeoutError}};
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+use crate::handler::{ErrorKind, Location, Part, SolutionHandler, SolveError, TimedSolution, Timing};
+use crate::limits::{AllocationScope, Limits};
+
+/// Set for solve worker processes, which read a request from stdin instead of parsing arguments
+const WORKER_ENV: &str = "ADVENT_SOLVE_WORKER";
+/// Marks the worker's result line, anything else it prints is ignored
+const RESULT_PREFIX: &str = "solve-worker-result: ";
+/// How often a worker checks its allocations against the memory ceiling
+const LIMIT_POLL: Duration = Duration::from_millis(10);
+
+/// How a solve worker is started, the running executable unless a test replaces it
+#[derive(Clone, Debug)]
+pub struct WorkerCommand {
+    pub program: PathBuf,
+    pub args: Vec<String>,
+}
+
+impl WorkerCommand {
+    pub fn current_exe() -> io::Result<WorkerCommand> {
+        Ok(WorkerCommand { program: env::current_exe()?, args: vec![] })
+    }
+}
+
+pub fn is_worker() -> bool {
+    env::var_os(WORKER_ENV).is_some()
+}
+
+/// Solves in a worker process that is killed once it runs over the timeout, the worker enforces the memory ceiling itself
+pub fn solve(command: &WorkerCommand, year: &str, day: &str, parts: &[Part], input: &str, limits: &Limits) -> Vec<TimedSolution> {
+    let failed = |timing: Timing, kind: ErrorKind, message: String| -> Vec<TimedSolution> {
+        parts.iter().map(|_| (timing, Err(SolveError::new(kind, message.clone())))).collect()
+    };
+    let start = Instant::now();
+    let spawned = Command::new(&command.program).args(&command.args).env(WORKER_ENV, "1")
+        .stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::inherit())
+        .spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(e) => return failed(Timing::default(), ErrorKind::Worker, format!("could not start a solve worker: {}", e)),
+    };
+
+    let request = json!({
+        "year": year,
+        "day": day,
+        "parts": parts.iter().map(|part| part.to_string()).collect::<Vec<_>>(),
+        "memory": limits.memory,
+        "input": input,
+    });
+    // a worker that stopped early closes stdin, its missing result is reported below
+    let mut stdin = child.stdin.take().expect("worker stdin is piped");
+    let _ = stdin.write_all(request.to_string().as_bytes());
+    drop(stdin);
+
+    let mut stdout = child.stdout.take().expect("worker stdout is piped");
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut output = String::new();
+        let _ = stdout.read_to_string(&mut output);
+        let _ = sender.send(output);
+    });
+    let output_res = match limits.timeout {
+        Some(timeout) => receiver.recv_timeout(timeout.saturating_sub(start.elapsed())),
+        None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+    };
+    let output = match output_res {
+        Ok(output) => output,
+        Err(_) => {
+            let _ = child.kill();
+            let _ = child.wait();
+            let message = format!("no result after {:.1}s", limits.timeout.unwrap_or_default().as_secs_f64());
+            return failed(Timing { parse: None, solve: start.elapsed() }, ErrorKind::Timeout, message);
+        },
+    };
+    let status = child.wait().map(|status| status.to_string()).unwrap_or_else(|e| e.to_string());
+
+    let solutions = output.lines()
+        .find_map(|line| line.strip_prefix(RESULT_PREFIX))
+        .and_then(|result| serde_json::from_str::<Value>(result).ok())
+        .and_then(|result| result.as_array().map(|solutions| solutions.iter().map(from_json).collect::<Vec<_>>()))
+        .filter(|solutions| solutions.len() == parts.len());
+    match solutions {
+        Some(solutions) => solutions,
+        None => failed(Timing { parse: None, solve: start.elapsed() }, ErrorKind::Panic, format!("solve worker stopped without a result ({})", status)),
+    }
+}
+
+/// Runs in the worker process, solves the request read from stdin and prints the result
+pub fn serve<'a>(solution_handler: &SolutionHandler<'a, &'a str>) {
+    let mut request = String::new();
+    let request = io::stdin().read_to_string(&mut request).ok()
+        .and_then(|_| serde_json::from_str::<Value>(&request).ok());
+    let request = match request {
+        Some(request) => request,
+        None => {
+            eprintln!("WARN: Unreadable solve worker request");
+            process::exit(1);
+        },
+    };
+    let text = |key: &str| request[key].as_str().unwrap_or_default().to_string();
+    let (year, day) = (text("year"), text("day"));
+    let parts: Vec<Part> = request["parts"].as_array().into_iter().flatten()
+        .filter_map(|part| part.as_str().and_then(|part| part.parse().ok()))
+        .collect();
+    // leaked as the registered handlers borrow their input for as long as they live
+    let input: &'a str = Box::leak(text("input").into_boxed_str());
+
+    let allocated = Arc::new(AtomicIsize::new(0));
+    if let Some(memory) = request["memory"].as_u64().map(|memory| memory as usize) {
+        let watched = Arc::clone(&allocated);
+        let watched_parts = parts.clone();
+        thread::spawn(move || loop {
+            thread::sleep(LIMIT_POLL);
+            let used = watched.load(Ordering::Relaxed).max(0) as usize;
+            if used > memory {
+                let message = format!("allocated {} MiB, over the {} MiB ceiling", used >> 20, memory >> 20);
+                let solutions: Vec<TimedSolution> = watched_parts.iter()
+                    .map(|_| (Timing::default(), Err(SolveError::new(ErrorKind::ResourceExceeded, message.clone()))))
+                    .collect();
+                print_result(&solutions);
+                process::exit(0);
+            }
+        });
+    }
+
+    let _scope = AllocationScope::enter(allocated);
+    let solutions = solution_handler.solve_parts(&year, &day, &parts, input);
+    print_result(&solutions);
+}
+
+/// Written straight to stdout, as the test harness captures `print!`
+fn print_result(solutions: &[TimedSolution]) {
+    let result = Value::Array(solutions.iter().map(to_json).collect());
+    let mut stdout = io::stdout().lock();
+    let _ = stdout.write_all(format!("\n{}{}\n", RESULT_PREFIX, result).as_bytes());
+    let _ = stdout.flush();
+}
+
+fn to_json((timing, result): &TimedSolution) -> Value {
+    let error = result.as_ref().err().map(|e| json!({
+        "kind": e.kind.to_string(),
+        "message": e.message,
+        "line": e.location.map(|location| location.line),
+        "column": e.location.and_then(|location| location.column),
+    }));
+    json!({
+        "parse_ns": timing.parse.map(|parse| parse.as_nanos() as u64),
+        "solve_ns": timing.solve.as_nanos() as u64,
+        "answer": result.as_ref().ok(),
+        "error": error,
+    })
+}
+
+fn from_json(solution: &Value) -> TimedSolution {
+    let timing = Timing {
+        parse: solution["parse_ns"].as_u64().map(Duration::from_nanos),
+        solve: Duration::from_nanos(solution["solve_ns"].as_u64().unwrap_or_default()),
+    };
+    let result = match solution["answer"].as_str() {
+        Some(answer) => Ok(answer.to_string()),
+        None => {
+            let error = &solution["error"];
+            let kind = error["kind"].as_str().and_then(|kind| kind.parse().ok()).unwrap_or(ErrorKind::Panic);
+            let mut solve_error = SolveError::new(kind, error["message"].as_str().unwrap_or_default());
+            solve_error.location = error["line"].as_u64().map(|line| Location { line: line as usize, column: error["column"].as_u64().map(|column| column as usize) });
+            Err(solve_error)
+        },
+    };
+
+    (timing, result)
+}
@@ -1,5 +1,5 @@
 use std::{num::ParseIntError, str::Split};
-use crate::handler::{SolveError, AdventSolution, DayHandler};
+use crate::handler::{SolveError, AdventSolution, Part, DayHandler, ErrorKind};
 
 
 #[derive(Debug)]
@@ -13,9 +13,11 @@ impl From<ParseIntError> for Day1Error {
     }
 }
 
-impl Into<SolveError> for Day1Error {
-    fn into(self) -> SolveError {
-        SolveError(format!("Day1Error: {:?}", self))
+impl From<Day1Error> for SolveError {
+    fn from(error: Day1Error) -> Self {
+        match error {
+            Day1Error::Parse(e) => SolveError::new(ErrorKind::Parse, e.to_string()).with_source(e),
+        }
     }
 }
 
@@ -98,12 +100,12 @@ impl<'a> Day1Handler {
 
 impl<'a> AdventSolution<&str> for Day1Handler {
     fn get_day(&self) -> String { String::from("1") }
-    fn solve(&self, problem: &str, input: &str) -> Result<String, SolveError> {
+    fn solve(&self, part: Part, input: &str) -> Result<String, SolveError> {
         let input_lines = input.split("\n");
-        let result = if problem == "1" {
-            self.solve_1(input_lines)
-        } else {
-            self.solve_2(input_lines)
+        let result = match part {
+            Part::One => self.solve_1(input_lines),
+            Part::Two => self.solve_2(input_lines),
+            _ => return Err(SolveError::unimplemented(&self.get_day(), part)),
         };
 
         result.map_err(|e| e.into())
@@ -1,13 +1,13 @@
 use std::{str::Split, fmt::Display, collections::VecDeque};
 
-use crate::handler::{AdventSolution, SolveError, DayHandler};
+use crate::handler::{AdventSolution, Part, SolveError, DayHandler};
 
 #[derive(Debug)]
 pub enum Day11Error {}
 
-impl Into<SolveError> for Day11Error {
-    fn into(self) -> SolveError {
-        SolveError(format!("Day11Error: {:?}", self))
+impl From<Day11Error> for SolveError {
+    fn from(error: Day11Error) -> Self {
+        match error {}
     }
 }
 
@@ -203,12 +203,12 @@ impl<'a> Day11Handler {
 
 impl<'a> AdventSolution<&str> for Day11Handler {
     fn get_day(&self) -> String { String::from("11") }
-    fn solve(&self, problem: &str, input: &str) -> Result<String, SolveError> {
+    fn solve(&self, part: Part, input: &str) -> Result<String, SolveError> {
         let input_lines = input.split("\n");
-        let result = if problem == "1" {
-            self.solve_1(input_lines)
-        } else {
-            self.solve_2(input_lines)
+        let result = match part {
+            Part::One => self.solve_1(input_lines),
+            Part::Two => self.solve_2(input_lines),
+            _ => return Err(SolveError::unimplemented(&self.get_day(), part)),
         };
 
         result.map_err(|e| e.into())
@@ -217,7 +217,7 @@ impl<'a> AdventSolution<&str> for Day11Handler {
 
 #[cfg(test)]
 mod tests {
-    use crate::handler::AdventSolution;
+    use crate::handler::{AdventSolution, Part};
     use super::Day11Handler;
 
     fn get_input<'a>() -> &'a str {
@@ -239,20 +239,20 @@ mod tests {
         assert!(&handler.get_day() == "11");
     }
 
-    async fn solution(sol: &str) -> String {
+    async fn solution(sol: Part) -> String {
         let handler = Day11Handler::new();
         handler.solve(sol, get_input()).unwrap()
     }
 
     #[tokio::test]
     async fn solution_1() {
-        let solution = solution("1").await;
+        let solution = solution(Part::One).await;
         assert!(solution == String::from("1656"));
     }
 
     #[tokio::test]
     async fn solution_2() {
-        let solution = solution("2").await;
+        let solution = solution(Part::Two).await;
         assert!(solution == String::from("195"));
     }
 }
@@ -1,14 +1,14 @@
 use std::collections::{HashMap, HashSet};
 use std::str::Split;
 
-use crate::handler::{AdventSolution, SolveError, DayHandler};
+use crate::handler::{AdventSolution, Part, SolveError, DayHandler};
 
 #[derive(Debug)]
 pub enum Day12Error {}
 
-impl Into<SolveError> for Day12Error {
-    fn into(self) -> SolveError {
-        SolveError(format!("Day12Error: {:?}", self))
+impl From<Day12Error> for SolveError {
+    fn from(error: Day12Error) -> Self {
+        match error {}
     }
 }
 
@@ -156,12 +156,12 @@ impl<'a> Day12Handler {
 
 impl<'a> AdventSolution<&str> for Day12Handler {
     fn get_day(&self) -> String { String::from("12") }
-    fn solve(&self, problem: &str, input: &str) -> Result<String, SolveError> {
+    fn solve(&self, part: Part, input: &str) -> Result<String, SolveError> {
         let input_lines = input.split("\n");
-        let result = if problem == "1" {
-            self.solve_1(input_lines)
-        } else {
-            self.solve_2(input_lines)
+        let result = match part {
+            Part::One => self.solve_1(input_lines),
+            Part::Two => self.solve_2(input_lines),
+            _ => return Err(SolveError::unimplemented(&self.get_day(), part)),
         };
 
         result.map_err(|e| e.into())
@@ -170,7 +170,7 @@ impl<'a> AdventSolution<&str> for Day12Handler {
 
 #[cfg(test)]
 mod tests {
-    use crate::handler::AdventSolution;
+    use crate::handler::{AdventSolution, Part};
     use super::Day12Handler;
 
     fn get_input<'a>() -> &'a str {
@@ -189,20 +189,20 @@ b-end"
         assert!(&handler.get_day() == "12");
     }
 
-    async fn solution(sol: &str) -> String {
+    async fn solution(sol: Part) -> String {
         let handler = Day12Handler::new();
         handler.solve(sol, get_input()).unwrap()
     }
 
     #[tokio::test]
     async fn solution_1() {
-        let solution = solution("1").await;
+        let solution = solution(Part::One).await;
         assert!(solution == String::from("10"));
     }
 
     #[tokio::test]
     async fn solution_2() {
-        let solution = solution("2").await;
+        let solution = solution(Part::Two).await;
         assert!(solution == String::from("36"));
     }
 }
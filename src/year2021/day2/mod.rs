@@ -1,6 +1,6 @@
 use std::{str::Split, num::ParseIntError};
 
-use crate::handler::{AdventSolution, SolveError, DayHandler};
+use crate::handler::{AdventSolution, Part, SolveError, DayHandler, ErrorKind};
 
 #[derive(Debug)]
 pub enum Day2Error { 
@@ -13,9 +13,11 @@ impl From<ParseIntError> for Day2Error {
     }
 }
 
-impl Into<SolveError> for Day2Error {
-    fn into(self) -> SolveError {
-        SolveError(format!("Day2Error: {:?}", self))
+impl From<Day2Error> for SolveError {
+    fn from(error: Day2Error) -> Self {
+        match error {
+            Day2Error::Parse(e) => SolveError::new(ErrorKind::Parse, e.to_string()).with_source(e),
+        }
     }
 }
 
@@ -72,12 +74,12 @@ impl<'a> Day2Handler {
 
 impl<'a> AdventSolution<&str> for Day2Handler {
     fn get_day(&self) -> String { String::from("2") }
-    fn solve(&self, problem: &str, input: &str) -> Result<String, SolveError> {
+    fn solve(&self, part: Part, input: &str) -> Result<String, SolveError> {
         let input_lines = input.split("\n");
-        let result = if problem == "1" {
-            self.solve_1(input_lines)
-        } else {
-            self.solve_2(input_lines)
+        let result = match part {
+            Part::One => self.solve_1(input_lines),
+            Part::Two => self.solve_2(input_lines),
+            _ => return Err(SolveError::unimplemented(&self.get_day(), part)),
         };
 
         result.map_err(|e| e.into())
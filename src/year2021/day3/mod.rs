@@ -1,6 +1,6 @@
 use std::{str::Split, num::ParseIntError};
 
-use crate::handler::{AdventSolution, SolveError, DayHandler};
+use crate::handler::{AdventSolution, Part, SolveError, DayHandler, ErrorKind};
 
 #[derive(Debug)]
 pub enum Day3Error {
@@ -13,9 +13,11 @@ impl From<ParseIntError> for Day3Error {
     }
 }
 
-impl Into<SolveError> for Day3Error {
-    fn into(self) -> SolveError {
-        SolveError(format!("Day3Error: {:?}", self))
+impl From<Day3Error> for SolveError {
+    fn from(error: Day3Error) -> Self {
+        match error {
+            Day3Error::Parse(e) => SolveError::new(ErrorKind::Parse, e.to_string()).with_source(e),
+        }
     }
 }
 
@@ -117,12 +119,12 @@ impl<'a> Day3Handler {
 
 impl<'a> AdventSolution<&str> for Day3Handler {
     fn get_day(&self) -> String { String::from("3") }
-    fn solve(&self, problem: &str, input: &str) -> Result<String, SolveError> {
+    fn solve(&self, part: Part, input: &str) -> Result<String, SolveError> {
         let input_lines = input.split("\n");
-        let result = if problem == "1" {
-            self.solve_1(input_lines)
-        } else {
-            self.solve_2(input_lines)
+        let result = match part {
+            Part::One => self.solve_1(input_lines),
+            Part::Two => self.solve_2(input_lines),
+            _ => return Err(SolveError::unimplemented(&self.get_day(), part)),
         };
 
         result.map_err(|e| e.into())
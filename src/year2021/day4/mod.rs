@@ -1,14 +1,25 @@
 use ndarray::{Array2, Axis};
-use std::str::Split;
+use std::{num::ParseIntError, str::Split};
 
-use crate::handler::{AdventSolution, SolveError, DayHandler};
+use crate::handler::{AdventSolution, Part, SolveError, DayHandler, ErrorKind};
 
+const BOARD_SIZE: usize = 5;
+
+/// Line indexes are 0-based positions in the input
 #[derive(Debug)]
-pub enum Day4Error {}
+pub enum Day4Error {
+    Parse(usize, ParseIntError),
+    InvalidInput(usize, String),
+    NoWinner,
+}
 
-impl Into<SolveError> for Day4Error {
-    fn into(self) -> SolveError {
-        SolveError(format!("Day4Error: {:?}", self))
+impl From<Day4Error> for SolveError {
+    fn from(error: Day4Error) -> Self {
+        match error {
+            Day4Error::Parse(line_idx, e) => SolveError::parse_line(line_idx, e),
+            Day4Error::InvalidInput(line_idx, message) => SolveError::new(ErrorKind::InvalidInput, message).at_line(line_idx + 1),
+            Day4Error::NoWinner => SolveError::new(ErrorKind::InvalidInput, "no board wins with the drawn numbers"),
+        }
     }
 }
 
@@ -20,7 +31,7 @@ impl Boards {
     pub fn new(boards_vec: Vec<Vec<Vec<(bool, u32)>>>) -> Boards {
         let mut boards: Vec<(bool, Array2<(bool, u32)>)> = vec![];
         for board in boards_vec {
-            let mut nd_board = Array2::<(bool, u32)>::default((5, 5));
+            let mut nd_board = Array2::<(bool, u32)>::default((BOARD_SIZE, BOARD_SIZE));
             for (i, mut row) in nd_board.axis_iter_mut(Axis(0)).enumerate() {
                 for (j, col) in row.iter_mut().enumerate() {
                     *col = board[i][j];
@@ -57,7 +68,7 @@ impl Boards {
 
     fn update_board(board: &mut Array2<(bool, u32)>, number: u32) {
         for board_row in board.rows_mut() {
-            for mut el in board_row {
+            for el in board_row {
                 if el.1 == number {
                     el.0 = true;
                 } 
@@ -82,18 +93,18 @@ pub struct Day4Handler {}
 impl<'a> Day4Handler {
     pub fn new() -> DayHandler<'a, &'a str> { DayHandler::new(Day4Handler {}) }
     pub fn solve_1(&self, input_lines: Split<&str>) -> Result<String, Day4Error> {
-        let (numbers, mut boards) = self.init(input_lines);
+        let (numbers, mut boards) = self.init(input_lines)?;
 
         let final_num_score_res = boards.process_numbers(numbers, &mut |number, board| {
             (true, (number, self.calculate_score(&board)))
         });
-        let final_num_score = final_num_score_res.unwrap();
+        let final_num_score = final_num_score_res.ok_or(Day4Error::NoWinner)?;
 
         Ok(format!("{}", final_num_score.0 * final_num_score.1))
     }
     
     pub fn solve_2(&self, input_lines: Split<&str>) -> Result<String, Day4Error> {
-        let (numbers, mut boards) = self.init(input_lines);
+        let (numbers, mut boards) = self.init(input_lines)?;
 
         let wins_needed = boards.len();
         let mut wins = 0;
@@ -101,39 +112,65 @@ impl<'a> Day4Handler {
             wins += 1;
             (wins == wins_needed, (number, self.calculate_score(&board)))
         });
-        let final_num_score = final_num_score_res.unwrap();
+        let final_num_score = final_num_score_res.ok_or(Day4Error::NoWinner)?;
 
         Ok(format!("{}", final_num_score.0 * final_num_score.1))
     }
 
-    fn init(&self, mut input_lines: Split<&str>) -> (Vec<u32>, Boards) {
-        let numbers: Vec<u32> = input_lines.next().unwrap().split(",")
-            .map(|val| val.parse::<u32>().unwrap()).collect();
-        let boards = self.get_boards(input_lines);
-        
-        (numbers, boards)
+    fn init(&self, mut input_lines: Split<&str>) -> Result<(Vec<u32>, Boards), Day4Error> {
+        let numbers = input_lines.next().unwrap_or_default().split(",")
+            .map(|val| val.trim().parse::<u32>().map_err(|e| Day4Error::Parse(0, e)))
+            .collect::<Result<Vec<u32>, Day4Error>>()?;
+        let boards = self.get_boards(input_lines)?;
+
+        Ok((numbers, boards))
     }
 
-    fn get_boards(&self, board_lines: Split<&str>) -> Boards {
+    fn get_boards(&self, board_lines: Split<&str>) -> Result<Boards, Day4Error> {
+        let check_complete = |board: &Vec<Vec<(bool, u32)>>, line_idx: usize| {
+            if board.is_empty() || board.len() == BOARD_SIZE {
+                Ok(())
+            } else {
+                Err(Day4Error::InvalidInput(line_idx, format!("board ends after {} rows, expected {}", board.len(), BOARD_SIZE)))
+            }
+        };
+
         let mut boards_vec: Vec<Vec<Vec<(bool, u32)>>> = vec![];
-        for line in board_lines {
-            if line.len() > 0 {
-                let board_line = self.process_bingo_line(line);
-                let board_idx = boards_vec.len() - 1;
-                let board = &mut boards_vec[board_idx];
+        let mut last_line_idx = 0;
+        // board lines follow the drawn numbers on the first line
+        for (line_idx, line) in board_lines.enumerate().map(|(idx, line)| (idx + 1, line)) {
+            last_line_idx = line_idx;
+            if !line.trim().is_empty() {
+                let board_line = self.process_bingo_line(line_idx, line)?;
+                if board_line.len() != BOARD_SIZE {
+                    return Err(Day4Error::InvalidInput(line_idx, format!("expected {} numbers in a board row, found {}", BOARD_SIZE, board_line.len())));
+                }
+                let board = boards_vec.last_mut()
+                    .ok_or_else(|| Day4Error::InvalidInput(line_idx, String::from("expected a blank line before the first board")))?;
+                if board.len() == BOARD_SIZE {
+                    return Err(Day4Error::InvalidInput(line_idx, format!("board has more than {} rows", BOARD_SIZE)));
+                }
                 board.push(board_line);
             } else {
+                if let Some(board) = boards_vec.last() {
+                    check_complete(board, line_idx)?;
+                }
                 boards_vec.push(vec![]);
             }
         }
+        if let Some(board) = boards_vec.last() {
+            check_complete(board, last_line_idx)?;
+        }
+        boards_vec.retain(|board| !board.is_empty());
 
-        Boards::new(boards_vec)
+        Ok(Boards::new(boards_vec))
     }
 
-    fn process_bingo_line(&self, line: &str) -> Vec<(bool, u32)> {
+    fn process_bingo_line(&self, line_idx: usize, line: &str) -> Result<Vec<(bool, u32)>, Day4Error> {
         line.split(" ")
             .filter(|val| val.len() > 0)
-            .map(|bingo_num| (false, bingo_num.parse::<u32>().unwrap())).collect()
+            .map(|bingo_num| bingo_num.parse::<u32>().map(|num| (false, num)).map_err(|e| Day4Error::Parse(line_idx, e)))
+            .collect()
     }
 
     fn calculate_score(&self, board: &Array2<(bool, u32)>) -> u32 {
@@ -143,12 +180,12 @@ impl<'a> Day4Handler {
 
 impl<'a> AdventSolution<&str> for Day4Handler {
     fn get_day(&self) -> String { String::from("4") }
-    fn solve(&self, problem: &str, input: &str) -> Result<String, SolveError> {
+    fn solve(&self, part: Part, input: &str) -> Result<String, SolveError> {
         let input_lines = input.split("\n");
-        let result = if problem == "1" {
-            self.solve_1(input_lines)
-        } else {
-            self.solve_2(input_lines)
+        let result = match part {
+            Part::One => self.solve_1(input_lines),
+            Part::Two => self.solve_2(input_lines),
+            _ => return Err(SolveError::unimplemented(&self.get_day(), part)),
         };
 
         result.map_err(|e| e.into())
@@ -157,7 +194,7 @@ impl<'a> AdventSolution<&str> for Day4Handler {
 
 #[cfg(test)]
 mod tests {
-    use crate::handler::AdventSolution;
+    use crate::handler::{AdventSolution, ErrorKind, Part};
     use super::Day4Handler;
     fn get_input<'a>() -> &'a str {
         "7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
@@ -184,14 +221,23 @@ mod tests {
     #[tokio::test]
     async fn solution_1() {
         let handler = Day4Handler::new();
-        let solution = handler.solve("1", get_input()).unwrap();
+        let solution = handler.solve(Part::One, get_input()).unwrap();
         assert!(solution == String::from("4512"), "Returned: {}, expected: {}", solution, "4512");
     }
 
     #[tokio::test]
     async fn solution_2() {
         let handler = Day4Handler::new();
-        let solution = handler.solve("2", get_input()).unwrap();
+        let solution = handler.solve(Part::Two, get_input()).unwrap();
         assert!(solution == String::from("1924"), "Returned: {}, expected: {}", solution, "1924");
     }
+
+    #[tokio::test]
+    async fn bad_input_line() {
+        let input = get_input().replacen("21  9 14 16  7", "21  9 x4 16  7", 1);
+        let handler = Day4Handler::new();
+        let error = handler.solve(Part::One, &input).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Parse);
+        assert_eq!(error.location.map(|location| location.line), Some(5));
+    }
 }
@@ -2,14 +2,14 @@ use std::{str::Split, ops::RangeInclusive, iter::Rev};
 
 use ndarray::Array2;
 
-use crate::handler::{AdventSolution, SolveError, DayHandler};
+use crate::handler::{AdventSolution, Part, SolveError, DayHandler};
 
 #[derive(Debug)]
 pub enum Day5Error {}
 
-impl Into<SolveError> for Day5Error {
-    fn into(self) -> SolveError {
-        SolveError(format!("Day5Error: {:?}", self))
+impl From<Day5Error> for SolveError {
+    fn from(error: Day5Error) -> Self {
+        match error {}
     }
 }
 
@@ -148,12 +148,12 @@ impl Iterator for AxisIter {
 
 impl<'a> AdventSolution<&str> for Day5Handler {
     fn get_day(&self) -> String { String::from("5") }
-    fn solve(&self, problem: &str, input: &str) -> Result<String, SolveError> {
+    fn solve(&self, part: Part, input: &str) -> Result<String, SolveError> {
         let input_lines = input.split("\n");
-        let result = if problem == "1" {
-            self.solve_1(input_lines)
-        } else {
-            self.solve_2(input_lines)
+        let result = match part {
+            Part::One => self.solve_1(input_lines),
+            Part::Two => self.solve_2(input_lines),
+            _ => return Err(SolveError::unimplemented(&self.get_day(), part)),
         };
 
         result.map_err(|e| e.into())
@@ -162,7 +162,7 @@ impl<'a> AdventSolution<&str> for Day5Handler {
 
 #[cfg(test)]
 mod tests {
-    use crate::handler::AdventSolution;
+    use crate::handler::{AdventSolution, Part};
     use super::Day5Handler;
 
     fn get_input<'a>() -> &'a str {
@@ -181,7 +181,7 @@ mod tests {
     #[tokio::test]
     async fn solution_1() {
         let handler = Day5Handler::new();
-        let solution = handler.solve("1", get_input()).unwrap();
+        let solution = handler.solve(Part::One, get_input()).unwrap();
         println!("soln1: {}", solution);
         assert!(solution == String::from("5"));
     }
@@ -189,7 +189,7 @@ mod tests {
     #[tokio::test]
     async fn solution_2() {
         let handler = Day5Handler::new();
-        let solution = handler.solve("2", get_input()).unwrap();
+        let solution = handler.solve(Part::Two, get_input()).unwrap();
         println!("soln2: {}", solution);
         assert!(solution == String::from("12"));
     }
@@ -1,9 +1,12 @@
-use std::{str::Split, collections::VecDeque, ops::Add};
+use std::{num::ParseIntError, str::Split, collections::VecDeque, ops::Add};
 
-use crate::handler::{AdventSolution, DayHandler, SolveError};
+use crate::handler::{AdventSolution, Part, DayHandler, SolveError, ErrorKind};
 
 #[derive(Debug)]
-pub enum Day6Error {}
+pub enum Day6Error {
+    Parse(ParseIntError),
+    InvalidTimer(u64),
+}
 
 #[derive(Clone, Debug)]
 struct LanternfishGroup(usize);
@@ -57,9 +60,12 @@ impl Iterator for Runtime {
     }
 }
 
-impl Into<SolveError> for Day6Error {
-    fn into(self) -> SolveError {
-        SolveError(format!("Day6Error: {:?}", self))
+impl From<Day6Error> for SolveError {
+    fn from(error: Day6Error) -> Self {
+        match error {
+            Day6Error::Parse(e) => SolveError::parse_line(0, e),
+            Day6Error::InvalidTimer(timer) => SolveError::new(ErrorKind::InvalidInput, format!("fish timer {} is above 8", timer)).at_line(1),
+        }
     }
 }
 
@@ -67,7 +73,12 @@ pub struct Day6Handler {}
 impl<'a> Day6Handler {
     pub fn new() -> DayHandler<'a, &'a str> { DayHandler::new(Day6Handler {}) }
     pub fn solve_challenge(&self, input_lines: Split<&str>, days: usize) -> Result<String, Day6Error> {
-        let initial_fish: Vec<u64> = input_lines.map(|days| days.parse::<u64>().unwrap()).collect();
+        let initial_fish = input_lines
+            .map(|days| days.trim().parse::<u64>().map_err(Day6Error::Parse))
+            .collect::<Result<Vec<u64>, Day6Error>>()?;
+        if let Some(timer) = initial_fish.iter().find(|timer| **timer > 8) {
+            return Err(Day6Error::InvalidTimer(*timer));
+        }
         let mut runtime = Runtime::new(initial_fish);
 
         let mut latest_fish = 0;
@@ -89,12 +100,12 @@ impl<'a> Day6Handler {
 
 impl<'a> AdventSolution<&str> for Day6Handler {
     fn get_day(&self) -> String { String::from("6") }
-    fn solve(&self, problem: &str, input: &str) -> Result<String, SolveError> {
+    fn solve(&self, part: Part, input: &str) -> Result<String, SolveError> {
         let input_lines = input.split(",");
-        let result = if problem == "1" {
-            self.solve_1(input_lines)
-        } else {
-            self.solve_2(input_lines)
+        let result = match part {
+            Part::One => self.solve_1(input_lines),
+            Part::Two => self.solve_2(input_lines),
+            _ => return Err(SolveError::unimplemented(&self.get_day(), part)),
         };
 
         result.map_err(|e| e.into())
@@ -103,7 +114,7 @@ impl<'a> AdventSolution<&str> for Day6Handler {
 
 #[cfg(test)]
 mod tests {
-    use crate::handler::AdventSolution;
+    use crate::handler::{AdventSolution, Part};
     use super::Day6Handler;
 
     fn get_input<'a>() -> &'a str {
@@ -113,14 +124,14 @@ mod tests {
     #[tokio::test]
     async fn solution_1() {
         let handler = Day6Handler::new();
-        let solution = handler.solve("1", get_input()).unwrap();
+        let solution = handler.solve(Part::One, get_input()).unwrap();
         assert!(solution == String::from("5934"));
     }
 
     #[tokio::test]
     async fn solution_2() {
         let handler = Day6Handler::new();
-        let solution = handler.solve("2", get_input()).unwrap();
+        let solution = handler.solve(Part::Two, get_input()).unwrap();
         assert!(solution == String::from("26984457539"));
     }
 }
@@ -1,13 +1,17 @@
-use std::str::Split;
+use std::{num::ParseIntError, str::Split};
 
-use crate::handler::{AdventSolution, SolveError, DayHandler};
+use crate::handler::{AdventSolution, Part, SolveError, DayHandler};
 
 #[derive(Debug)]
-pub enum Day7Error {}
+pub enum Day7Error {
+    Parse(ParseIntError),
+}
 
-impl Into<SolveError> for Day7Error {
-    fn into(self) -> SolveError {
-        SolveError(format!("Day7Error: {:?}", self))
+impl From<Day7Error> for SolveError {
+    fn from(error: Day7Error) -> Self {
+        match error {
+            Day7Error::Parse(e) => SolveError::parse_line(0, e),
+        }
     }
 }
 
@@ -15,8 +19,10 @@ pub struct Day7Handler {}
 impl<'a> Day7Handler {
     pub fn new() ->  DayHandler<'a, &'a str> { DayHandler::new(Day7Handler {}) }
 
-    fn solve_shared(&self, input_lines: Split<&str>, calc_cost: &dyn Fn(isize) -> isize) -> isize {
-        let input: Vec<isize> = input_lines.map(|num| num.parse::<isize>().unwrap()).collect();
+    fn solve_shared(&self, input_lines: Split<&str>, calc_cost: &dyn Fn(isize) -> isize) -> Result<isize, Day7Error> {
+        let input = input_lines
+            .map(|num| num.trim().parse::<isize>().map_err(Day7Error::Parse))
+            .collect::<Result<Vec<isize>, Day7Error>>()?;
 
         let mut min_cost = isize::MAX;
         let max = input.iter().fold(0, |max, val| *val.max(&max));
@@ -30,28 +36,28 @@ impl<'a> Day7Handler {
             if total_cost < min_cost { min_cost = total_cost; }
         }
         
-        min_cost
+        Ok(min_cost)
     }
 
     pub fn solve_1(&self, input_lines: Split<&str>) -> Result<String, Day7Error> {
-        let min_cost = self.solve_shared(input_lines, &|dist| dist);
+        let min_cost = self.solve_shared(input_lines, &|dist| dist)?;
         Ok(format!("{}", min_cost))
     }
     
     pub fn solve_2(&self, input_lines: Split<&str>) -> Result<String, Day7Error> {
-        let min_cost = self.solve_shared(input_lines.clone(), &|dist| dist * (dist + 1) / 2);
+        let min_cost = self.solve_shared(input_lines.clone(), &|dist| dist * (dist + 1) / 2)?;
         Ok(format!("{}", min_cost))
     }
 }
 
 impl<'a> AdventSolution<&str> for Day7Handler {
     fn get_day(&self) -> String { String::from("7") }
-    fn solve(&self, problem: &str, input: &str) -> Result<String, SolveError> {
+    fn solve(&self, part: Part, input: &str) -> Result<String, SolveError> {
         let input_lines = input.split(",");
-        let result = if problem == "1" {
-            self.solve_1(input_lines)
-        } else {
-            self.solve_2(input_lines)
+        let result = match part {
+            Part::One => self.solve_1(input_lines),
+            Part::Two => self.solve_2(input_lines),
+            _ => return Err(SolveError::unimplemented(&self.get_day(), part)),
         };
 
         result.map_err(|e| e.into())
@@ -60,7 +66,7 @@ impl<'a> AdventSolution<&str> for Day7Handler {
 
 #[cfg(test)]
 mod tests {
-    use crate::handler::AdventSolution;
+    use crate::handler::{AdventSolution, Part};
     use super::Day7Handler;
 
     fn get_input<'a>() -> &'a str {
@@ -73,20 +79,20 @@ mod tests {
         assert!(&handler.get_day() == "7");
     }
 
-    async fn solution(sol: &str) -> String {
+    async fn solution(sol: Part) -> String {
         let handler = Day7Handler::new();
         handler.solve(sol, get_input()).unwrap()
     }
 
     #[tokio::test]
     async fn solution_1() {
-        let solution = solution("1").await;
+        let solution = solution(Part::One).await;
         assert!(solution == String::from("37"));
     }
 
     #[tokio::test]
     async fn solution_2() {
-        let solution = solution("2").await;
+        let solution = solution(Part::Two).await;
         assert!(solution == String::from("168"));
     }
 }
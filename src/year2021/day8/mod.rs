@@ -1,14 +1,14 @@
 use std::str::Split;
 use std::collections::HashMap;
 
-use crate::handler::{AdventSolution, SolveError, DayHandler};
+use crate::handler::{AdventSolution, Part, SolveError, DayHandler};
 
 #[derive(Debug)]
 pub enum Day8Error {}
 
-impl Into<SolveError> for Day8Error {
-    fn into(self) -> SolveError {
-        SolveError(format!("Day8Error: {:?}", self))
+impl From<Day8Error> for SolveError {
+    fn from(error: Day8Error) -> Self {
+        match error {}
     }
 }
 
@@ -86,12 +86,12 @@ impl<'a> Day8Handler {
 
 impl<'a> AdventSolution<&str> for Day8Handler {
     fn get_day(&self) -> String { String::from("8") }
-    fn solve(&self, problem: &str, input: &str) -> Result<String, SolveError> {
+    fn solve(&self, part: Part, input: &str) -> Result<String, SolveError> {
         let input_lines = input.split("\n");
-        let result = if problem == "1" {
-            self.solve_1(input_lines)
-        } else {
-            self.solve_2(input_lines)
+        let result = match part {
+            Part::One => self.solve_1(input_lines),
+            Part::Two => self.solve_2(input_lines),
+            _ => return Err(SolveError::unimplemented(&self.get_day(), part)),
         };
 
         result.map_err(|e| e.into())
@@ -100,7 +100,7 @@ impl<'a> AdventSolution<&str> for Day8Handler {
 
 #[cfg(test)]
 mod tests {
-    use crate::handler::AdventSolution;
+    use crate::handler::{AdventSolution, Part};
     use super::Day8Handler;
 
     fn get_input<'a>() -> &'a str {
@@ -122,20 +122,20 @@ mod tests {
         assert!(&handler.get_day() == "8");
     }
 
-    async fn solution(sol: &str) -> String {
+    async fn solution(sol: Part) -> String {
         let handler = Day8Handler::new();
         handler.solve(sol, get_input()).unwrap()
     }
 
     #[tokio::test]
     async fn solution_1() {
-        let solution = solution("1").await;
+        let solution = solution(Part::One).await;
         assert!(solution == String::from("26"));
     }
 
     #[tokio::test]
     async fn solution_2() {
-        let solution = solution("2").await;
+        let solution = solution(Part::Two).await;
         assert!(solution == String::from("61229"));
     }
 }
@@ -1,3 +1,5 @@
+use crate::handler::DayHandler;
+
 mod day1;
 mod day2;
 mod day3;
@@ -27,4 +29,22 @@ pub mod prelude {
   pub use super::day12::Day12Handler;
   pub use super::day13::Day13Handler;
   // all day handlers
-}
\ No newline at end of file
+}
+
+pub fn handlers<'a>() -> Vec<DayHandler<'a, &'a str>> {
+  vec![
+    prelude::Day1Handler::new(),
+    prelude::Day2Handler::new(),
+    prelude::Day3Handler::new(),
+    prelude::Day4Handler::new(),
+    prelude::Day5Handler::new(),
+    prelude::Day6Handler::new(),
+    prelude::Day7Handler::new(),
+    prelude::Day8Handler::new(),
+    prelude::Day9Handler::new(),
+    prelude::Day10Handler::new(),
+    prelude::Day11Handler::new(),
+    prelude::Day12Handler::new(),
+    prelude::Day13Handler::new(),
+  ]
+}
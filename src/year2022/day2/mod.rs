@@ -1,13 +1,13 @@
 use std::str::Split;
 
-use crate::handler::{AdventSolution, SolveError, DayHandler};
+use crate::handler::{AdventSolution, Part, SolveError, DayHandler};
 
 #[derive(Debug)]
 pub enum Day2Error {}
 
-impl Into<SolveError> for Day2Error {
-    fn into(self) -> SolveError {
-        SolveError(format!("Day2Error: {:?}", self))
+impl From<Day2Error> for SolveError {
+    fn from(error: Day2Error) -> Self {
+        match error {}
     }
 }
 
@@ -126,12 +126,12 @@ impl<'a> Day2Handler {
 
 impl<'a> AdventSolution<&str> for Day2Handler {
     fn get_day(&self) -> String { String::from("2") }
-    fn solve(&self, problem: &str, input: &str) -> Result<String, SolveError> {
+    fn solve(&self, part: Part, input: &str) -> Result<String, SolveError> {
         let input_lines = input.split("\n");
-        let result = if problem == "1" {
-            self.solve_1(input_lines)
-        } else {
-            self.solve_2(input_lines)
+        let result = match part {
+            Part::One => self.solve_1(input_lines),
+            Part::Two => self.solve_2(input_lines),
+            _ => return Err(SolveError::unimplemented(&self.get_day(), part)),
         };
 
         result.map_err(|e| e.into())
@@ -140,7 +140,7 @@ impl<'a> AdventSolution<&str> for Day2Handler {
 
 #[cfg(test)]
 mod tests {
-    use crate::handler::AdventSolution;
+    use crate::handler::{AdventSolution, Part};
     use super::Day2Handler;
 
     fn get_input<'a>() -> &'a str {
@@ -156,20 +156,20 @@ C Z
         assert!(&handler.get_day() == "2");
     }
 
-    async fn solution(sol: &str) -> String {
+    async fn solution(sol: Part) -> String {
         let handler = Day2Handler::new();
         handler.solve(sol, get_input()).unwrap()
     }
 
     #[tokio::test]
     async fn solution_1() {
-        let solution = solution("1").await;
+        let solution = solution(Part::One).await;
         assert!(solution == String::from("15"));
     }
 
     #[tokio::test]
     async fn solution_2() {
-        let solution = solution("2").await;
+        let solution = solution(Part::Two).await;
         assert!(solution == String::from("12"));
     }
 }
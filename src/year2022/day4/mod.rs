@@ -1,13 +1,20 @@
-use std::str::Split;
+use std::{num::ParseIntError, str::Split};
 
-use crate::handler::{AdventSolution, SolveError, DayHandler};
+use crate::handler::{AdventSolution, Part, SolveError, DayHandler, ErrorKind};
 
+/// Line indexes are 0-based positions in the input
 #[derive(Debug)]
-pub enum Day4Error {}
+pub enum Day4Error {
+    Parse(usize, ParseIntError),
+    InvalidInput(usize, String),
+}
 
-impl Into<SolveError> for Day4Error {
-    fn into(self) -> SolveError {
-        SolveError(format!("Day4Error: {:?}", self))
+impl From<Day4Error> for SolveError {
+    fn from(error: Day4Error) -> Self {
+        match error {
+            Day4Error::Parse(line_idx, e) => SolveError::parse_line(line_idx, e),
+            Day4Error::InvalidInput(line_idx, message) => SolveError::new(ErrorKind::InvalidInput, message).at_line(line_idx + 1),
+        }
     }
 }
 
@@ -17,12 +24,20 @@ struct Pair {
 }
 
 impl Pair {
-    pub fn new(pair_str: &str) -> Self {
-        let pair_split: Vec<&str> = pair_str.split("-").collect();
-        let start = pair_split[0].parse::<usize>().unwrap();
-        let end = pair_split[1].parse::<usize>().unwrap();
+    pub fn new(line_idx: usize, pair_str: &str) -> Result<Self, Day4Error> {
+        let (start, end) = pair_str.split_once("-")
+            .ok_or_else(|| Day4Error::InvalidInput(line_idx, format!("expected a range like 2-4, found '{}'", pair_str)))?;
+        let start = start.parse::<usize>().map_err(|e| Day4Error::Parse(line_idx, e))?;
+        let end = end.parse::<usize>().map_err(|e| Day4Error::Parse(line_idx, e))?;
+
+        Ok(Pair { start, end })
+    }
 
-        Pair { start, end }
+    pub fn from_line(line_idx: usize, line: &str) -> Result<(Self, Self), Day4Error> {
+        let (first, second) = line.split_once(",")
+            .ok_or_else(|| Day4Error::InvalidInput(line_idx, format!("expected two comma separated ranges, found '{}'", line)))?;
+
+        Ok((Pair::new(line_idx, first)?, Pair::new(line_idx, second)?))
     }
 
     pub fn contains(&self, other_pair: &Pair) -> bool {
@@ -39,37 +54,31 @@ pub struct Day4Handler {}
 impl<'a> Day4Handler {
     pub fn new() -> DayHandler<'a, &'a str> { DayHandler::new(Day4Handler {}) }
     pub fn solve_1(&self, input_lines: Split<&str>) -> Result<String, Day4Error> {
-        let result = input_lines.fold(0usize, |count, pair| {
-            if pair.len() <= 0 {
-                return count;
+        let mut result = 0usize;
+        for (line_idx, line) in input_lines.enumerate() {
+            if line.is_empty() {
+                continue;
             }
-            let pairs: Vec<&str> = pair.split(",").collect();
-            let first_pair = Pair::new(pairs[0]);
-            let second_pair = Pair::new(pairs[1]);
+            let (first_pair, second_pair) = Pair::from_line(line_idx, line)?;
             if first_pair.contains(&second_pair) || second_pair.contains(&first_pair) {
-                return count + 1;
+                result += 1;
             }
-
-            count
-        });
+        }
 
         Ok(result.to_string())
     }
     
     pub fn solve_2(&self, input_lines: Split<&str>) -> Result<String, Day4Error> {
-        let result = input_lines.fold(0usize, |count, pair| {
-            if pair.len() <= 0 {
-                return count;
+        let mut result = 0usize;
+        for (line_idx, line) in input_lines.enumerate() {
+            if line.is_empty() {
+                continue;
             }
-            let pairs: Vec<&str> = pair.split(",").collect();
-            let first_pair = Pair::new(pairs[0]);
-            let second_pair = Pair::new(pairs[1]);
+            let (first_pair, second_pair) = Pair::from_line(line_idx, line)?;
             if first_pair.overlaps(&second_pair) || second_pair.overlaps(&first_pair) {
-                return count + 1;
+                result += 1;
             }
-
-            count
-        });
+        }
 
         Ok(result.to_string())
     }
@@ -77,12 +86,12 @@ impl<'a> Day4Handler {
 
 impl<'a> AdventSolution<&str> for Day4Handler {
     fn get_day(&self) -> String { String::from("4") }
-    fn solve(&self, problem: &str, input: &str) -> Result<String, SolveError> {
+    fn solve(&self, part: Part, input: &str) -> Result<String, SolveError> {
         let input_lines = input.split("\n");
-        let result = if problem == "1" {
-            self.solve_1(input_lines)
-        } else {
-            self.solve_2(input_lines)
+        let result = match part {
+            Part::One => self.solve_1(input_lines),
+            Part::Two => self.solve_2(input_lines),
+            _ => return Err(SolveError::unimplemented(&self.get_day(), part)),
         };
 
         result.map_err(|e| e.into())
@@ -91,7 +100,7 @@ impl<'a> AdventSolution<&str> for Day4Handler {
 
 #[cfg(test)]
 mod tests {
-    use crate::handler::AdventSolution;
+    use crate::handler::{AdventSolution, Part};
     use super::Day4Handler;
 
     fn get_input<'a>() -> &'a str {
@@ -110,20 +119,20 @@ mod tests {
         assert!(&handler.get_day() == "4");
     }
 
-    async fn solution(sol: &str) -> String {
+    async fn solution(sol: Part) -> String {
         let handler = Day4Handler::new();
         handler.solve(sol, get_input()).unwrap()
     }
 
     #[tokio::test]
     async fn solution_1() {
-        let solution = solution("1").await;
+        let solution = solution(Part::One).await;
         assert!(solution == String::from("2"));
     }
 
     #[tokio::test]
     async fn solution_2() {
-        let solution = solution("2").await;
-        assert!(solution == String::from(""));
+        let solution = solution(Part::Two).await;
+        assert!(solution == String::from("4"));
     }
 }
@@ -4,14 +4,14 @@ use std::{
     str::Split,
 };
 
-use crate::handler::{AdventSolution, DayHandler, SolveError};
+use crate::handler::{AdventSolution, Part, DayHandler, SolveError};
 
 #[derive(Debug)]
 pub enum Day6Error {}
 
-impl Into<SolveError> for Day6Error {
-    fn into(self) -> SolveError {
-        SolveError(format!("Day6Error: {:?}", self))
+impl From<Day6Error> for SolveError {
+    fn from(error: Day6Error) -> Self {
+        match error {}
     }
 }
 
@@ -61,12 +61,12 @@ impl<'a> AdventSolution<&str> for Day6Handler {
     fn get_day(&self) -> String {
         String::from("6")
     }
-    fn solve(&self, problem: &str, input: &str) -> Result<String, SolveError> {
+    fn solve(&self, part: Part, input: &str) -> Result<String, SolveError> {
         let input_lines = input.split("\n");
-        let result = if problem == "1" {
-            self.solve_1(input_lines)
-        } else {
-            self.solve_2(input_lines)
+        let result = match part {
+            Part::One => self.solve_1(input_lines),
+            Part::Two => self.solve_2(input_lines),
+            _ => return Err(SolveError::unimplemented(&self.get_day(), part)),
         };
 
         result.map_err(|e| e.into())
@@ -76,7 +76,7 @@ impl<'a> AdventSolution<&str> for Day6Handler {
 #[cfg(test)]
 mod tests {
     use super::Day6Handler;
-    use crate::handler::AdventSolution;
+    use crate::handler::{AdventSolution, Part};
 
     fn get_input<'a>() -> &'a str {
         "bvwbjplbgvbhsrlpgdmjqwftvncz"
@@ -88,20 +88,20 @@ mod tests {
         assert!(&handler.get_day() == "6");
     }
 
-    async fn solution(sol: &str) -> String {
+    async fn solution(sol: Part) -> String {
         let handler = Day6Handler::new();
         handler.solve(sol, get_input()).unwrap()
     }
 
     #[tokio::test]
     async fn solution_1() {
-        let solution = solution("1").await;
+        let solution = solution(Part::One).await;
         assert!(solution == String::from("5"));
     }
 
     #[tokio::test]
     async fn solution_2() {
-        let solution = solution("2").await;
+        let solution = solution(Part::Two).await;
         assert!(solution == String::from("23"));
     }
 }
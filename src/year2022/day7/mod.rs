@@ -1,18 +1,36 @@
-use std::{collections::HashMap, path::PathBuf, rc::Rc, cell::RefCell};
-use pest::{Parser, iterators::Pair};
+use std::{collections::HashMap, num::ParseIntError, path::PathBuf, rc::Rc, cell::RefCell};
+use pest::{Parser, error::{ErrorVariant, LineColLocation}, iterators::Pair};
 
-use crate::handler::{AdventSolution, SolveError, DayHandler};
+use crate::handler::{ParsedSolution, Part, SolveError, DayHandler, ErrorKind};
 
 #[derive(Parser)]
 #[grammar = "year2022/day7/command.pest"]
 struct CommandParser;
 
 #[derive(Debug)]
-pub enum Day7Error {}
+pub enum Day7Error {
+    Parse(Box<pest::error::Error<Rule>>),
+    Size(ParseIntError),
+    InvalidInput(String),
+}
 
-impl Into<SolveError> for Day7Error {
-    fn into(self) -> SolveError {
-        SolveError(format!("Day7Error: {:?}", self))
+impl From<Day7Error> for SolveError {
+    fn from(error: Day7Error) -> Self {
+        match error {
+            Day7Error::Parse(e) => {
+                let (line, column) = match e.line_col {
+                    LineColLocation::Pos(pos) => pos,
+                    LineColLocation::Span(start, _) => start,
+                };
+                let message = match &e.variant {
+                    ErrorVariant::ParsingError { positives, .. } => format!("expected one of {:?}", positives),
+                    ErrorVariant::CustomError { message } => message.clone(),
+                };
+                SolveError::new(ErrorKind::Parse, message).at(line, column).with_source(*e)
+            },
+            Day7Error::Size(e) => SolveError::new(ErrorKind::Parse, e.to_string()).with_source(e),
+            Day7Error::InvalidInput(message) => SolveError::new(ErrorKind::InvalidInput, message),
+        }
     }
 }
 
@@ -23,22 +41,37 @@ enum FSCommand {
 }
 
 impl FSCommand {
-    fn from_pair(pair: Pair<Rule>) -> FSCommand {
+    fn from_pair(pair: Pair<Rule>) -> Result<FSCommand, Day7Error> {
         match pair.as_rule() {
             Rule::cd_command => {
                 let mut data_pairs = pair.into_inner();
-                let cd_str = data_pairs.next().unwrap().as_str();
-                FSCommand::CD(cd_str.to_string())
+                let cd_str = data_pairs.next().map(|data| data.as_str()).unwrap_or_default();
+                Ok(FSCommand::CD(cd_str.to_string()))
             },
             Rule::ls_command => {
                 let ls_data: Vec<String> = pair
                     .into_inner()
                     .map(|pair| pair.as_str().to_string())
                     .collect();
-                FSCommand::LS(ls_data)
+                Ok(FSCommand::LS(ls_data))
             },
-            _ => { panic!("unsupported command: {:?}", pair) },
+            _ => Err(Day7Error::InvalidInput(format!("unsupported command: {}", pair.as_str()))),
+        }
+    }
+
+    fn parse_file(input: &str) -> Result<Vec<FSCommand>, Day7Error> {
+        let mut commands = CommandParser::parse(Rule::command_file, input).map_err(|e| Day7Error::Parse(Box::new(e)))?;
+        let mut command_list: Vec<FSCommand> = vec![];
+        if let Some(command_file) = commands.next() {
+            for command_line in command_file.into_inner() {
+                let command_info_opt = command_line.into_inner().next();
+                if let Some(command_info) = command_info_opt {
+                    command_list.push(FSCommand::from_pair(command_info)?);
+                }
+            }
         }
+
+        Ok(command_list)
     }
 }
 
@@ -57,25 +90,25 @@ struct FSObject {
 }
 
 impl FSObject {
-    pub fn from_str(curr_path: &str, obj_str: &str) -> Self {
-        let mut line_pairs = CommandParser::parse(Rule::fs_object, obj_str).unwrap();
-        let line_pair = line_pairs.next().unwrap().into_inner().next().unwrap();
+    pub fn from_str(curr_path: &str, obj_str: &str) -> Result<Self, Day7Error> {
+        let invalid = || Day7Error::InvalidInput(format!("unknown ls output: {}", obj_str));
+        let mut line_pairs = CommandParser::parse(Rule::fs_object, obj_str).map_err(|e| Day7Error::Parse(Box::new(e)))?;
+        let line_pair = line_pairs.next().and_then(|pair| pair.into_inner().next()).ok_or_else(invalid)?;
         match line_pair.as_rule() {
             Rule::ls_dir => {
-                let line_match = line_pair.into_inner().next().unwrap().as_str();
+                let line_match = line_pair.into_inner().next().ok_or_else(invalid)?.as_str();
                 let mut dir_path_buf = PathBuf::new();
                 dir_path_buf.push(curr_path);
                 dir_path_buf.push(line_match);
-                return FSObject::new_dir(&dir_path_buf.to_str().unwrap());
+                Ok(FSObject::new_dir(&dir_path_buf.to_string_lossy()))
             },
             Rule::ls_file => {
                 let mut pairs = line_pair.into_inner();
-                let ls_file_size = pairs.next().map(|size| size.as_str().parse::<usize>().unwrap()).unwrap();
-                let ls_file_name = pairs.next().unwrap().as_str();
-                return FSObject::new_file(ls_file_name, ls_file_size);
-
+                let ls_file_size = pairs.next().ok_or_else(invalid)?.as_str().parse::<usize>().map_err(Day7Error::Size)?;
+                let ls_file_name = pairs.next().ok_or_else(invalid)?.as_str();
+                Ok(FSObject::new_file(ls_file_name, ls_file_size))
             },
-            _ => panic!("unknown ls rule: {}", line_pair)
+            _ => Err(invalid()),
         }
     }
 
@@ -97,9 +130,9 @@ impl FSObject {
 }
 
 #[derive(Debug)]
-struct FileSystem {
+pub struct FileSystem {
     curr_dir: Box<PathBuf>,
-    pub directories: HashMap<String, Rc<RefCell<FSObject>>>,
+    directories: HashMap<String, Rc<RefCell<FSObject>>>,
 }
 
 impl FileSystem {
@@ -110,7 +143,7 @@ impl FileSystem {
         FileSystem { curr_dir: Box::new(PathBuf::new()), directories }
     }
 
-    pub fn execute(&mut self, command: FSCommand) {
+    fn execute(&mut self, command: FSCommand) -> Result<(), Day7Error> {
         match command {
             FSCommand::CD(path) => {
                 if &path == ".." {
@@ -120,13 +153,10 @@ impl FileSystem {
                 }
             },
             FSCommand::LS(list) => {
-                let curr_path = self.curr_dir.to_str().unwrap();
-                let fs_children = list.iter().fold(vec![], |mut children, line| {
-                    let child_fs_object = FSObject::from_str(curr_path, line);
-                    children.push(child_fs_object);
-
-                    children
-                });
+                let curr_path = self.curr_dir.to_string_lossy().to_string();
+                let fs_children = list.iter()
+                    .map(|line| FSObject::from_str(&curr_path, line))
+                    .collect::<Result<Vec<FSObject>, Day7Error>>()?;
 
                 let directory_keys: Vec<String> = self.directories.keys().map(|key| key.clone()).collect();
                 
@@ -138,37 +168,35 @@ impl FileSystem {
                     if child_type == FSObjectType::Directory && !directory_keys.contains(&child_name) {
                         self.directories.insert(child_name, Rc::clone(&rc_child));
                     }
-                    let fs_object = self.directories.get_mut(curr_path).unwrap();
+                    let fs_object = self.directories.get_mut(&curr_path)
+                        .ok_or_else(|| Day7Error::InvalidInput(format!("ls in unknown directory: {}", curr_path)))?;
                     fs_object.borrow_mut().children.push(Rc::clone(&rc_child));
                 }
             },
         };
+
+        Ok(())
     }
 }
 
 pub struct Day7Handler {}
 impl<'a> Day7Handler {
-    pub fn new() -> DayHandler<'a, &'a str> { DayHandler::new(Day7Handler {}) }
-    pub fn solve_1(&self, input: &str) -> Result<String, Day7Error> {
-        let mut commands = CommandParser::parse(Rule::command_file, input.clone()).unwrap();
-        let command_file = commands.next().unwrap();
-        let mut command_list: Vec<FSCommand> = vec![];
-        for command_line in command_file.into_inner() {
-            let command_info_opt = command_line.into_inner().next();
-            if let Some(command_info) = command_info_opt {
-                command_list.push(FSCommand::from_pair(command_info));
-            }
-        }
-
+    pub fn new() -> DayHandler<'a, &'a str> { DayHandler::parsed(Day7Handler {}) }
+    fn build_file_system(&self, input: &str) -> Result<FileSystem, Day7Error> {
+        let command_list = FSCommand::parse_file(input)?;
         let mut file_system = FileSystem::new();
 
         for command in command_list {
-            file_system.execute(command);
+            file_system.execute(command)?;
         }
-        
+
+        Ok(file_system)
+    }
+
+    pub fn solve_1(&self, file_system: &FileSystem) -> Result<String, Day7Error> {
         let mut total = 0;
-        for directory in file_system.directories {
-            let dir_size = directory.1.borrow().size();
+        for directory in file_system.directories.values() {
+            let dir_size = directory.borrow().size();
             if dir_size <= 100000 {
                 total = total + dir_size;
             }
@@ -177,30 +205,16 @@ impl<'a> Day7Handler {
         Ok(total.to_string())
     }
     
-    pub fn solve_2(&self, input: &str) -> Result<String, Day7Error> {
-        let mut commands = CommandParser::parse(Rule::command_file, input.clone()).unwrap();
-        let command_file = commands.next().unwrap();
-        let mut command_list: Vec<FSCommand> = vec![];
-        for command_line in command_file.into_inner() {
-            let command_info_opt = command_line.into_inner().next();
-            if let Some(command_info) = command_info_opt {
-                command_list.push(FSCommand::from_pair(command_info));
-            }
-        }
-
-        let mut file_system = FileSystem::new();
-
-        for command in command_list {
-            file_system.execute(command);
-        }
-        
+    pub fn solve_2(&self, file_system: &FileSystem) -> Result<String, Day7Error> {
         let available_disk: usize = 70000000;
-        let used = file_system.directories.get("/").unwrap().borrow().size();
+        let used = file_system.directories.get("/")
+            .ok_or_else(|| Day7Error::InvalidInput(String::from("no root directory")))?
+            .borrow().size();
         let unused = available_disk - used;
         let needed = 30000000;
         let mut min_delete = available_disk;
-        for directory in file_system.directories {
-            let dir_size = directory.1.borrow().size();
+        for directory in file_system.directories.values() {
+            let dir_size = directory.borrow().size();
 
             if unused + dir_size >= needed && min_delete > dir_size {
                 min_delete = dir_size;
@@ -211,13 +225,18 @@ impl<'a> Day7Handler {
     }
 }
 
-impl<'a> AdventSolution<&str> for Day7Handler {
+impl ParsedSolution for Day7Handler {
+    type Parsed = FileSystem;
+
     fn get_day(&self) -> String { String::from("7") }
-    fn solve(&self, problem: &str, input: &str) -> Result<String, SolveError> {
-        let result = if problem == "1" {
-            self.solve_1(input)
-        } else {
-            self.solve_2(input)
+    fn parse(&self, input: &str) -> Result<FileSystem, SolveError> {
+        self.build_file_system(input).map_err(|e| e.into())
+    }
+    fn solve_parsed(&self, part: Part, file_system: &FileSystem) -> Result<String, SolveError> {
+        let result = match part {
+            Part::One => self.solve_1(file_system),
+            Part::Two => self.solve_2(file_system),
+            _ => return Err(SolveError::unimplemented(&self.get_day(), part)),
         };
 
         result.map_err(|e| e.into())
@@ -226,7 +245,7 @@ impl<'a> AdventSolution<&str> for Day7Handler {
 
 #[cfg(test)]
 mod tests {
-    use crate::handler::AdventSolution;
+    use crate::handler::{AdventSolution, ErrorKind, Part};
     use super::Day7Handler;
 
     fn get_input<'a>() -> &'a str {
@@ -262,20 +281,28 @@ $ ls
         assert!(&handler.get_day() == "7");
     }
 
-    async fn solution(sol: &str) -> String {
+    async fn solution(sol: Part) -> String {
         let handler = Day7Handler::new();
         handler.solve(sol, get_input()).unwrap()
     }
 
     #[tokio::test]
     async fn solution_1() {
-        let solution = solution("1").await;
+        let solution = solution(Part::One).await;
         assert!(solution == String::from("95437"));
     }
 
     #[tokio::test]
     async fn solution_2() {
-        let solution = solution("2").await;
+        let solution = solution(Part::Two).await;
         assert!(solution == String::from("24933642"));
     }
+
+    #[test]
+    fn bad_input_location() {
+        let handler = Day7Handler::new();
+        let error = handler.solve(Part::One, "$ cd /\n$ ls\n$ rm a\n").unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Parse);
+        assert_eq!(error.location.map(|location| location.line), Some(3));
+    }
 }
@@ -1,3 +1,5 @@
+use crate::handler::DayHandler;
+
 mod day1;
 mod day2;
 mod day3;
@@ -17,4 +19,17 @@ pub mod prelude {
   pub use super::day7::Day7Handler;
   pub use super::day8::Day8Handler;
   // all day handlers
-}
\ No newline at end of file
+}
+
+pub fn handlers<'a>() -> Vec<DayHandler<'a, &'a str>> {
+  vec![
+    prelude::Day1Handler::new(),
+    prelude::Day2Handler::new(),
+    prelude::Day3Handler::new(),
+    prelude::Day4Handler::new(),
+    prelude::Day5Handler::new(),
+    prelude::Day6Handler::new(),
+    prelude::Day7Handler::new(),
+    prelude::Day8Handler::new(),
+  ]
+}
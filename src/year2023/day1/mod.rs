@@ -1,13 +1,13 @@
 use std::{collections::HashMap, str::Split};
 
-use crate::handler::{AdventSolution, DayHandler, SolveError};
+use crate::handler::{AdventSolution, Part, DayHandler, SolveError};
 
 #[derive(Debug)]
 pub enum Day1Error {}
 
-impl Into<SolveError> for Day1Error {
-    fn into(self) -> SolveError {
-        SolveError(format!("Day1Error: {:?}", self))
+impl From<Day1Error> for SolveError {
+    fn from(error: Day1Error) -> Self {
+        match error {}
     }
 }
 
@@ -172,12 +172,12 @@ impl<'a> AdventSolution<&str> for Day1Handler {
     fn get_day(&self) -> String {
         String::from("1")
     }
-    fn solve(&self, problem: &str, input: &str) -> Result<String, SolveError> {
+    fn solve(&self, part: Part, input: &str) -> Result<String, SolveError> {
         let input_lines = input.split("\n");
-        let result = if problem == "1" {
-            self.solve_1(input_lines)
-        } else {
-            self.solve_2(input_lines)
+        let result = match part {
+            Part::One => self.solve_1(input_lines),
+            Part::Two => self.solve_2(input_lines),
+            _ => return Err(SolveError::unimplemented(&self.get_day(), part)),
         };
 
         result.map_err(|e| e.into())
@@ -187,7 +187,7 @@ impl<'a> AdventSolution<&str> for Day1Handler {
 #[cfg(test)]
 mod tests {
     use super::Day1Handler;
-    use crate::handler::AdventSolution;
+    use crate::handler::{AdventSolution, Part};
 
     fn get_input_1<'a>() -> &'a str {
         "1abc2
@@ -215,14 +215,14 @@ zoneight234
     #[tokio::test]
     async fn solution_1() {
         let handler = Day1Handler::new();
-        let solution = handler.solve("1", get_input_1()).unwrap();
+        let solution = handler.solve(Part::One, get_input_1()).unwrap();
         assert!(solution == String::from("142"));
     }
 
     #[tokio::test]
     async fn solution_2() {
         let handler = Day1Handler::new();
-        let solution = handler.solve("2", get_input_2()).unwrap();
+        let solution = handler.solve(Part::Two, get_input_2()).unwrap();
         assert!(solution == String::from("281"));
     }
 }
@@ -1,14 +1,14 @@
 use std::str::Split;
 use std::collections::VecDeque;
 
-use crate::handler::{AdventSolution, DayHandler, SolveError};
+use crate::handler::{AdventSolution, Part, DayHandler, SolveError};
 
 #[derive(Debug)]
 pub enum Day3Error {}
 
-impl Into<SolveError> for Day3Error {
-    fn into(self) -> SolveError {
-        SolveError(format!("Day3Error: {:?}", self))
+impl From<Day3Error> for SolveError {
+    fn from(error: Day3Error) -> Self {
+        match error {}
     }
 }
 
@@ -176,12 +176,12 @@ impl<'a> AdventSolution<&str> for Day3Handler {
     fn get_day(&self) -> String {
         String::from("3")
     }
-    fn solve(&self, problem: &str, input: &str) -> Result<String, SolveError> {
+    fn solve(&self, part: Part, input: &str) -> Result<String, SolveError> {
         let input_lines = input.split("\n");
-        let result = if problem == "1" {
-            self.solve_1(input_lines)
-        } else {
-            self.solve_2(input_lines)
+        let result = match part {
+            Part::One => self.solve_1(input_lines),
+            Part::Two => self.solve_2(input_lines),
+            _ => return Err(SolveError::unimplemented(&self.get_day(), part)),
         };
 
         result.map_err(|e| e.into())
@@ -191,7 +191,7 @@ impl<'a> AdventSolution<&str> for Day3Handler {
 #[cfg(test)]
 mod tests {
     use super::Day3Handler;
-    use crate::handler::AdventSolution;
+    use crate::handler::{AdventSolution, Part};
 
     fn get_input<'a>() -> &'a str {
         "467..114..
@@ -212,20 +212,20 @@ mod tests {
         assert!(&handler.get_day() == "3");
     }
 
-    async fn solution(sol: &str) -> String {
+    async fn solution(sol: Part) -> String {
         let handler = Day3Handler::new();
         handler.solve(sol, get_input()).unwrap()
     }
 
     #[tokio::test]
     async fn solution_1() {
-        let solution = solution("1").await;
+        let solution = solution(Part::One).await;
         assert!(solution == String::from("4361"));
     }
 
     #[tokio::test]
     async fn solution_2() {
-        let solution = solution("2").await;
+        let solution = solution(Part::Two).await;
         assert!(solution == String::from("467835"));
     }
 }
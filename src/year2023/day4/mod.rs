@@ -1,13 +1,13 @@
 use std::str::Split;
 
-use crate::handler::{AdventSolution, DayHandler, SolveError};
+use crate::handler::{AdventSolution, Part, DayHandler, SolveError};
 
 #[derive(Debug)]
 pub enum Day4Error {}
 
-impl Into<SolveError> for Day4Error {
-    fn into(self) -> SolveError {
-        SolveError(format!("Day4Error: {:?}", self))
+impl From<Day4Error> for SolveError {
+    fn from(error: Day4Error) -> Self {
+        match error {}
     }
 }
 
@@ -88,12 +88,12 @@ impl<'a> AdventSolution<&str> for Day4Handler {
     fn get_day(&self) -> String {
         String::from("4")
     }
-    fn solve(&self, problem: &str, input: &str) -> Result<String, SolveError> {
+    fn solve(&self, part: Part, input: &str) -> Result<String, SolveError> {
         let input_lines = input.split("\n");
-        let result = if problem == "1" {
-            self.solve_1(input_lines)
-        } else {
-            self.solve_2(input_lines)
+        let result = match part {
+            Part::One => self.solve_1(input_lines),
+            Part::Two => self.solve_2(input_lines),
+            _ => return Err(SolveError::unimplemented(&self.get_day(), part)),
         };
 
         result.map_err(|e| e.into())
@@ -103,7 +103,7 @@ impl<'a> AdventSolution<&str> for Day4Handler {
 #[cfg(test)]
 mod tests {
     use super::Day4Handler;
-    use crate::handler::AdventSolution;
+    use crate::handler::{AdventSolution, Part};
 
     fn get_input<'a>() -> &'a str {
         "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
@@ -120,20 +120,20 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"
         assert!(&handler.get_day() == "4");
     }
 
-    async fn solution(sol: &str) -> String {
+    async fn solution(sol: Part) -> String {
         let handler = Day4Handler::new();
         handler.solve(sol, get_input()).unwrap()
     }
 
     #[tokio::test]
     async fn solution_1() {
-        let solution = solution("1").await;
+        let solution = solution(Part::One).await;
         assert!(solution == String::from("13"));
     }
 
     #[tokio::test]
     async fn solution_2() {
-        let solution = solution("2").await;
+        let solution = solution(Part::Two).await;
         assert!(solution == String::from("30"));
     }
 }
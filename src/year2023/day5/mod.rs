@@ -1,13 +1,13 @@
 use std::{collections::HashMap, str::Split};
 
-use crate::handler::{AdventSolution, DayHandler, SolveError};
+use crate::handler::{AdventSolution, Part, DayHandler, SolveError};
 
 #[derive(Debug)]
 pub enum Day5Error {}
 
-impl Into<SolveError> for Day5Error {
-    fn into(self) -> SolveError {
-        SolveError(format!("Day5Error: {:?}", self))
+impl From<Day5Error> for SolveError {
+    fn from(error: Day5Error) -> Self {
+        match error {}
     }
 }
 
@@ -179,12 +179,12 @@ impl<'a> AdventSolution<&str> for Day5Handler {
     fn get_day(&self) -> String {
         String::from("5")
     }
-    fn solve(&self, problem: &str, input: &str) -> Result<String, SolveError> {
+    fn solve(&self, part: Part, input: &str) -> Result<String, SolveError> {
         let input_lines = input.split("\n");
-        let result = if problem == "1" {
-            self.solve_1(input_lines)
-        } else {
-            self.solve_2(input_lines)
+        let result = match part {
+            Part::One => self.solve_1(input_lines),
+            Part::Two => self.solve_2(input_lines),
+            _ => return Err(SolveError::unimplemented(&self.get_day(), part)),
         };
 
         result.map_err(|e| e.into())
@@ -194,7 +194,7 @@ impl<'a> AdventSolution<&str> for Day5Handler {
 #[cfg(test)]
 mod tests {
     use super::Day5Handler;
-    use crate::handler::AdventSolution;
+    use crate::handler::{AdventSolution, Part};
 
     fn get_input<'a>() -> &'a str {
         "seeds: 79 14 55 13
@@ -238,20 +238,20 @@ humidity-to-location map:
         assert!(&handler.get_day() == "5");
     }
 
-    async fn solution(sol: &str) -> String {
+    async fn solution(sol: Part) -> String {
         let handler = Day5Handler::new();
         handler.solve(sol, get_input()).unwrap()
     }
 
     #[tokio::test]
     async fn solution_1() {
-        let solution = solution("1").await;
+        let solution = solution(Part::One).await;
         assert!(solution == String::from("35"));
     }
 
     #[tokio::test]
     async fn solution_2() {
-        let solution = solution("2").await;
+        let solution = solution(Part::Two).await;
         assert!(solution == String::from("46"));
     }
 }
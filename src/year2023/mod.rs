@@ -1,3 +1,5 @@
+use crate::handler::DayHandler;
+
 mod day1;
 mod day2;
 mod day3;
@@ -13,4 +15,15 @@ pub mod prelude {
   pub use super::day5::Day5Handler;
   pub use super::day6::Day6Handler;
   // all day handlers
-}
\ No newline at end of file
+}
+
+pub fn handlers<'a>() -> Vec<DayHandler<'a, &'a str>> {
+  vec![
+    prelude::Day1Handler::new(),
+    prelude::Day2Handler::new(),
+    prelude::Day3Handler::new(),
+    prelude::Day4Handler::new(),
+    prelude::Day5Handler::new(),
+    prelude::Day6Handler::new(),
+  ]
+}